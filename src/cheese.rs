@@ -1,4 +1,8 @@
-use crate::{cheese_builder::PossPath, pieces_map::PiecesMap, prev_pieces::PrevPieces};
+use crate::{
+    cheese_builder::{PossPath, SolverConfig},
+    pieces_map::PiecesMap,
+    prev_pieces::PrevPieces,
+};
 
 pub struct NewSide {
     //neue Seite, die an das Käsestück angefügt wird
@@ -18,18 +22,23 @@ impl NewSide {
 }
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Piece(pub u32, pub u32);
+impl Piece {
+    ///erzeugt eine Scheibe, die längere Seite steht immer vorne
+    pub fn new(a: u32, b: u32) -> Self {
+        if a >= b {
+            Self(a, b)
+        } else {
+            Self(b, a)
+        }
+    }
+}
 impl TryFrom<Vec<&str>> for Piece {
     type Error = std::num::ParseIntError;
     fn try_from(value: Vec<&str>) -> Result<Self, Self::Error> {
         assert!(value.len() == 2);
         let width = value[0].parse()?;
         let height = value[1].parse()?;
-        if width > height {
-            // panic!();
-            Ok(Self(width, height))
-        } else {
-            Ok(Self(height, width))
-        }
+        Ok(Self::new(width, height))
     }
 }
 
@@ -65,53 +74,60 @@ impl Cheese {
         size.sort_unstable_by_key(|size| std::cmp::Reverse(*size));
         Cheese { size }
     }
-    ///findet fehlende Scheiben (siehe Dokumentation)
+    ///fügt eine Scheibe an eine passende Seite des Käses an
+    ///gibt None zurück, falls die Scheibe an keine Seite passt
+    pub fn add_piece(&self, piece: Piece) -> Option<Cheese> {
+        let side_n = self
+            .get_sides()
+            .into_iter()
+            .position(|side| side == piece)?;
+        Some(self.expand_side(side_n))
+    }
+    ///findet die erste von bis zu max_run direkt aufeinanderfolgenden fehlenden Scheiben,
+    ///nach denen wieder eine echte Scheibe passt (siehe Dokumentation),
+    ///mit max_run = 1 also eine einzelne fehlende Scheibe, auf die eine echte Scheibe folgt
+    ///die weiteren fehlenden Scheiben einer Folge werden in den nächsten Schritten gefunden
     fn find_missing(
         &self,
         updated_sides: Vec<bool>, //welche Seiten bereits vergrößert wurden
         pieces: &PiecesMap,       //welche Scheiben noch vorhanden sind
+        max_run: usize,
     ) -> Vec<NewSide> {
-        //sucht in den verbleibenden Scheiben nach einer die zu einem vergrößerten Käsestück passt
-        Cheese::get_sides_n() //welche Seitenlängen für vergrößert werden
+        if max_run == 0 {
+            return vec![];
+        }
+        //gleiche Seiten ergeben nach dem Vergrößern denselben Käse
+        //und werden wie in find_new_sides nur einmal verwendet
+        let mut sides_seen = vec![];
+        self.get_sides()
             .into_iter()
-            .enumerate() //seite, die in den Scheiben gesucht wird
-            .flat_map(|(i_searched, (other_a, other_b))| {
-                if updated_sides[other_a] && updated_sides[other_b] {
-                    //falls beide Seiten bereits vergrößert wurden, kann es hier keine fehlende Scheibe geben
-                    return vec![];
-                }
-                //Der Käse aus der Sicht der Scheibe die nach der fehlenden Scheibe kommt
-                let len_x = self.size[other_a];
-                let len_y = self.size[other_b];
-                let len_z = self.size[i_searched];
-
-                //möglicherweise fehlende Scheiben, sowie die gesuchte Scheibe danach
-                let mut poss_missing = vec![];
-                if !updated_sides[other_a] {
-                    let missing = Piece(len_y, len_z);
-                    let searched = Piece(len_x + 1, len_y);
-                    poss_missing.push((other_a, missing, searched))
-                }
-                if !updated_sides[other_b] {
-                    let missing = Piece(len_x, len_z);
-                    let searched = Piece(len_x, len_y + 1);
-                    poss_missing.push((other_b, missing, searched))
+            .enumerate()
+            .filter(|(i, side)| {
+                //an vergrößerte Seiten passt eine echte Scheibe, dort fehlt keine
+                if updated_sides[*i] || sides_seen.contains(side) {
+                    false
+                } else {
+                    sides_seen.push(*side);
+                    true
                 }
-                poss_missing
             })
-            //gibt nur Scheiben zurück, die wirklich fehlen,
-            //d.h. es wird auch die Scheibe gefunden, die danach kommt
-            .filter_map(|(added_i, added, next)| {
-                //sortiert Scheiben aus, für die es keine darauf folgende Scheibe gibt
-                let n_pieces = pieces.get(&next)?;
-                if n_pieces == &0 {
-                    return None;
-                }
-                //gibt die fehlende Scheibe als NewSide zurück
-                //true gibt an, dass die Scheibe hinzugefügt ist
-                Some(NewSide::new(added_i, added, true))
+            .filter(|(i, _)| {
+                self.expand_side(*i)
+                    .real_piece_reachable(pieces, max_run - 1)
             })
-            .collect::<Vec<_>>()
+            //true gibt an, dass die Scheibe hinzugefügt ist
+            .map(|(i, side)| NewSide::new(i, side, true))
+            .collect()
+    }
+    ///gibt true zurück, wenn nach höchstens depth fehlenden Scheiben eine echte Scheibe passt
+    fn real_piece_reachable(&self, pieces: &PiecesMap, depth: usize) -> bool {
+        let has_real = self
+            .get_sides()
+            .iter()
+            .any(|side| pieces.get(side).is_some_and(|n| *n > 0));
+        has_real
+            || (depth > 0
+                && (0..3).any(|n| self.expand_side(n).real_piece_reachable(pieces, depth - 1)))
     }
     //findet neue Seiten, die an den Käse angefügt werden können
     pub fn find_new_sides(&self, pieces: &PiecesMap) -> (Vec<bool>, Vec<NewSide>) {
@@ -120,7 +136,7 @@ impl Cheese {
         let mut sides_seen = vec![];
         //welche Seiten bereits vergrößert wurden
         //wird nur benötigt, wenn man bei der Suche nach fehlenden Scheiben auch sucht,
-        //wenn Seiten gefunden wurden (also momentan nicht, villeicht aber in Zukunft)
+        //wenn Seiten gefunden wurden (SolverConfig::eager_missing)
         let mut updated_sides = vec![false; 3];
         //finde neue Seiten, die an den Käse angefügt werden können
         let new_sides = self
@@ -199,14 +215,19 @@ impl Cheese {
         &self,
         path: PrevPieces,
         pieces: Box<PiecesMap>,
-        find_missing: bool,
+        config: &SolverConfig,
     ) -> Vec<PossPath> {
         //findet neue Seiten, die an den Käse angefügt werden können
         let (updated_sides, mut new_sides) = self.find_new_sides(&pieces);
         // sucht nach fehlenden Scheiben, falls keine neuen Seiten gefunden wurden und
-        // es möglicherweise fehlende Scheiben gibt
-        if new_sides.is_empty() && find_missing {
-            new_sides = self.find_missing(updated_sides, &pieces);
+        // es möglicherweise fehlende Scheiben gibt, mit config.eager_missing auch neben
+        // den echten Scheiben, die zuerst in new_sides stehen und so bevorzugt werden
+        if config.find_missing && (new_sides.is_empty() || config.eager_missing) {
+            //eine Folge fehlender Scheiben darf höchstens max_missing_run lang werden
+            let max_run = config
+                .max_missing_run
+                .saturating_sub(path.run_added as usize);
+            new_sides.extend(self.find_missing(updated_sides, &pieces, max_run));
         }
         if !new_sides.is_empty() {
             //erzeugt mögliche Pfade, indem es Scheiben an die Seiten des Käses anfügt
@@ -224,3 +245,39 @@ impl From<Piece> for Cheese {
         Cheese::new([value.0, value.1, 1])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_pieces_match_the_side_they_are_added_to() {
+        // Nach der fehlenden Scheibe 4×2 wird der Käse 4×3×2 und die Scheibe 3×2 passt
+        let cheese = Cheese::new([4, 2, 2]);
+        let pieces = PiecesMap::new(&vec![Piece(3, 2)]);
+        let missing = cheese.find_missing(vec![false; 3], &pieces, 1);
+        assert!(!missing.is_empty());
+        for new_side in missing {
+            assert!(new_side.is_added);
+            assert_eq!(new_side.piece, cheese.get_sides()[new_side.side_n]);
+        }
+    }
+    #[test]
+    fn finds_first_of_two_missing_pieces() {
+        // Die Scheibe 5×3 passt erst nach zwei fehlenden Scheiben, z.B. 4×2 und 3×2
+        let cheese = Cheese::new([4, 2, 2]);
+        let pieces = PiecesMap::new(&vec![Piece(5, 3)]);
+        assert!(cheese.find_missing(vec![false; 3], &pieces, 1).is_empty());
+        let missing = cheese.find_missing(vec![false; 3], &pieces, 2);
+        let mut found = missing
+            .iter()
+            .map(|new_side| new_side.piece)
+            .collect::<Vec<_>>();
+        found.sort_unstable_by_key(|piece| (piece.0, piece.1));
+        // 4×2 ist zweimal eine Seite des Käses, wird aber nur einmal gefunden
+        assert_eq!(found, vec![Piece(2, 2), Piece(4, 2)]);
+        for new_side in missing {
+            assert_eq!(new_side.piece, cheese.get_sides()[new_side.side_n]);
+        }
+    }
+}
@@ -1,9 +1,13 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, collections::hash_map::Entry, hash::Hash};
+
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
     cheese::{Cheese, Piece},
     pieces_map::PiecesMap,
     prev_pieces::PrevPieces,
+    solution::{Solution, SolutionPiece},
+    volume::{candidate_sizes, FeasibleSizes},
 };
 //Ein möglicher Pfad
 pub struct PossPath {
@@ -21,83 +25,563 @@ impl PossPath {
             pieces_left: pieces,
         }
     }
+    ///gibt den Zustand der Suche zurück, Pfade mit demselben Käse und denselben übrigen Scheiben
+    ///haben denselben Zustand und werden gleich fortgesetzt (der Umkehrschluss gilt nur fast sicher)
+    fn state(&self) -> ([u32; 3], u64, u32) {
+        (
+            self.cheese.size,
+            self.prev_pieces.used_hash,
+            self.prev_pieces.run_added,
+        )
+    }
+    ///trägt den Zustand key des Pfads in seen ein, gibt false zurück, wenn schon ein Pfad mit
+    ///denselben echten Scheiben diesen Zustand erreicht hat, bei gleichem Zustand, aber anderen
+    ///Scheiben wird der Pfad weiterverfolgt
+    fn insert_state<K: Hash + Eq>(&self, seen: &mut FxHashMap<K, PrevPieces>, key: K) -> bool {
+        match seen.entry(key) {
+            Entry::Occupied(entry) => !entry.get().same_real_pieces(&self.prev_pieces),
+            Entry::Vacant(entry) => {
+                entry.insert(self.prev_pieces.clone());
+                true
+            }
+        }
+    }
     ///erzeugt neue Pfade, indem es die neuen Seiten an den Käse anfügt
-    fn gen_new_paths(self, find_missing: bool) -> Vec<PossPath> {
+    fn gen_new_paths(self, config: &SolverConfig) -> Vec<PossPath> {
         self.cheese
-            .gen_poss_paths(self.prev_pieces, self.pieces_left, find_missing)
+            .gen_poss_paths(self.prev_pieces, self.pieces_left, config)
+    }
+}
+///Einstellungen der Suche
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolverConfig {
+    pub find_missing: bool, //ob nach fehlenden Scheiben gesucht werden soll
+    //wie viele fehlende Scheiben direkt hintereinander vermutet werden dürfen
+    pub max_missing_run: usize,
+    //wie viele Scheiben am Anfang der Schnittreihenfolge fehlen dürfen (nur mit find_missing)
+    pub max_missing_start: usize,
+    //ob auch dann nach fehlenden Scheiben gesucht wird, wenn echte Scheiben passen
+    //(nur mit find_missing)
+    pub eager_missing: bool,
+    //wie viele fehlende Scheiben ein Käse höchstens haben darf
+    pub max_missing: Option<u32>,
+    //wie viele fehlende Scheiben alle Käse zusammen höchstens haben dürfen
+    pub max_missing_total: Option<u32>,
+    //ob Pfade, die auf verschiedenen Wegen denselben Zustand erreichen, zusammengeführt werden
+    //(dadurch werden mit detect_ambiguity nicht mehr alle anderen Lösungen gefunden)
+    pub transpositions: bool,
+    //die bekannte Größe des ursprünglichen Käses, nur Käse dieser Größe werden gefunden,
+    //mit find_missing werden fehlende äußere Scheiben bis zu dieser Größe ergänzt
+    pub target: Option<[u32; 3]>,
+    //das bekannte Volumen des ursprünglichen Käses, nur Käse mit diesem Volumen werden gefunden
+    pub target_volume: Option<u64>,
+    //ob nach einem gefundenen Käse die übrigen Pfade des Schritts noch erzeugt werden,
+    //um andere Lösungen mit gleich vielen Scheiben zu finden, die im selben Schritt enden
+    //(Lösungen, deren Pfade vorher verworfen wurden, werden so nicht gefunden)
+    pub detect_ambiguity: bool,
+    //ob die Scheiben mit Backtracking genau in möglichst wenige Käse zerlegt werden sollen,
+    //anstatt Käse nacheinander mit einer Mindestlänge zu suchen (siehe partition_cheeses)
+    pub exact: bool,
+    //in wie viele Käse die Scheiben höchstens zerlegt werden dürfen (nur mit exact)
+    pub max_cheeses: Option<usize>,
+    //ob alle Scheiben von einem Käse stammen, dann werden Pfade verworfen, die keinen Käse
+    //aus allen Scheiben mehr ergeben können (siehe FeasibleSizes, nur ohne find_missing)
+    pub single_cheese: bool,
+}
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            find_missing: false,
+            max_missing_run: 1,
+            max_missing_start: 0,
+            eager_missing: false,
+            max_missing: None,
+            max_missing_total: None,
+            transpositions: false,
+            target: None,
+            target_volume: None,
+            detect_ambiguity: false,
+            exact: false,
+            max_cheeses: None,
+            single_cheese: false,
+        }
     }
 }
-//Lässt aus den top-Paths nur den Pfad mit den wenigsten hinzugefügten Stücken über
-fn filter_top_paths(paths: Vec<Vec<PossPath>>) -> Vec<Vec<PossPath>> {
+///ein Käse, für den ein anderer Pfad im selben Schritt mit einer anderen Lösung geendet hat
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ambiguity {
+    pub cheese_i: usize, //der wievielte gefundene Käse
+    //wie viele Lösungen im selben Schritt geendet haben, die gewählte eingeschlossen
+    pub n_solutions: usize,
+    pub alternative: Solution, //eine andere Lösung
+    pub diverges_at: usize,    //die erste Scheibe, an der sich die Lösungen unterscheiden
+}
+///wie viele Startscheiben ausprobiert und aus welchem Grund andere verworfen wurden,
+///zusammengezählt über alle Suchen nach einem Käse (siehe select_starts)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StartSelection {
+    pub n_tried: usize, //wie viele Startscheiben ausprobiert wurden
+    //wie viele verworfen wurden, weil keine andere Scheibe an sie passt
+    pub n_unextendable: usize,
+    //wie viele verworfen wurden, weil ihre kürzere Seite länger ist als die längere Seite
+    //einer anderen Scheibe desselben Käses
+    pub n_too_thick: usize,
+    //wie viele verworfen wurden, weil keine der möglichen Größen zu ihnen passt
+    pub n_infeasible: usize,
+}
+///Statistiken über die Suche
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    pub peak_frontier: usize, //die größte Anzahl gleichzeitig verfolgter Pfade
+    pub n_paths: usize,       //wie viele Pfade insgesamt erzeugt wurden
+    //wie viele Pfade verworfen wurden, weil sie zu viele fehlende Scheiben hatten
+    pub n_over_budget: usize,
+    //wie viele Pfade mit einem anderen Pfad mit gleichem Zustand zusammengeführt wurden
+    pub n_transpositions: usize,
+    //wie oft ein fertiger Käse wieder verworfen wurde,
+    //weil sich die übrigen Scheiben nicht zerlegen ließen (nur mit SolverConfig::exact)
+    pub n_backtracks: usize,
+    //wie viele Pfade verworfen wurden, weil sie keine mögliche Größe mehr erreichen können
+    pub n_infeasible: usize,
+    //wie die Startscheiben ausgewählt wurden
+    pub starts: StartSelection,
+    //Käse, für die eine andere Lösung im selben Schritt geendet hat,
+    //nur mit SolverConfig::detect_ambiguity
+    pub ambiguities: Vec<Ambiguity>,
+}
+//Lässt aus den top-Paths nur den Pfad mit den wenigsten Folgen hinzugefügter Stücke über,
+//bei gleich vielen Folgen den mit den wenigsten hinzugefügten Stücken
+//Eine Folge zählt nur einmal, da mehrere direkt hintereinander gegessene Scheiben
+//genauso wahrscheinlich sind wie eine einzelne (siehe SolverConfig::max_missing_run)
+//mit config.eager_missing bleiben alle Startstücke mit bis zu EAGER_SLACK mehr hinzugefügten
+//Stücken übrig, da ein Startstück in der Mitte des Käses am Anfang weniger Stücke hinzufügt
+fn filter_top_paths(mut paths: Vec<Vec<PossPath>>, config: &SolverConfig) -> Vec<Vec<PossPath>> {
+    if config.eager_missing {
+        let group_added = |path: &Vec<PossPath>| {
+            path.iter()
+                .map(|poss_path| poss_path.prev_pieces.n_added)
+                .min()
+                .unwrap()
+        };
+        let min_added = paths.iter().map(group_added).min().unwrap();
+        paths.retain(|path| group_added(path) <= min_added + EAGER_SLACK);
+        return paths;
+    }
     let min_added_path = paths
         .into_iter()
         .min_by_key(|path| {
             path.iter()
-                .min_by_key(|poss_path| poss_path.prev_pieces.n_added)
+                .map(|poss_path| (poss_path.prev_pieces.n_runs, poss_path.prev_pieces.n_added))
+                .min()
                 .unwrap()
-                .prev_pieces
-                .n_added
         })
         .unwrap();
     vec![min_added_path]
 }
-//lässt aus den sub-Paths nur den Pfad mit den wenigsten hinzugefügten Stücken übrig
-//entfernt die Pfade mit mehr hinzugefügten Stücken
-fn filter_sub_paths(mut paths: Vec<PossPath>) -> Vec<PossPath> {
-    let min_added = paths.iter().fold(u32::MAX, |acc, poss_path| {
-        acc.min(poss_path.prev_pieces.n_added)
-    });
-    paths.retain(|poss_path| poss_path.prev_pieces.n_added <= min_added);
+//lässt aus den sub-Paths nur den Pfad mit den wenigsten Folgen hinzugefügter Stücke übrig,
+//mit config.max_missing_run = 1 also den mit den wenigsten hinzugefügten Stücken
+//mit config.eager_missing werden auch Pfade mit bis zu EAGER_SLACK mehr hinzugefügten
+//Stücken behalten, aber nach hinten sortiert, damit echte Scheiben bevorzugt werden
+fn filter_sub_paths(mut paths: Vec<PossPath>, config: &SolverConfig) -> Vec<PossPath> {
+    if config.eager_missing {
+        let min_added = paths.iter().fold(u32::MAX, |acc, poss_path| {
+            acc.min(poss_path.prev_pieces.n_added)
+        });
+        paths.retain(|poss_path| poss_path.prev_pieces.n_added <= min_added + EAGER_SLACK);
+        paths.sort_by_key(|poss_path| poss_path.prev_pieces.n_added);
+    } else {
+        let min_runs = paths.iter().fold(u32::MAX, |acc, poss_path| {
+            acc.min(poss_path.prev_pieces.n_runs)
+        });
+        paths.retain(|poss_path| poss_path.prev_pieces.n_runs <= min_runs);
+    }
     paths
 }
+//wie viele hinzugefügte Stücke ein Pfad mit config.eager_missing mehr haben darf
+//als der beste Pfad mit demselben Startstück
+const EAGER_SLACK: u32 = 1;
+//Ein gefundener Käse zusammen mit dem Pfad, der zu ihm geführt hat
+type FoundCheese = (Cheese, PrevPieces);
+//Entfernt die Pfade, die keine der möglichen Größen mehr erreichen können,
+//gibt zurück, wie viele Pfade entfernt wurden
+fn retain_feasible(paths: &mut Vec<PossPath>, feasible: Option<&FeasibleSizes>) -> usize {
+    let Some(feasible) = feasible else {
+        return 0;
+    };
+    let n_paths = paths.len();
+    paths.retain(|poss_path| {
+        feasible.can_reach(&poss_path.prev_pieces.start_piece, poss_path.cheese.size)
+    });
+    n_paths - paths.len()
+}
+//Berechnet die möglichen Größen der Käse aus den Einstellungen,
+//mit last muss der Käse alle übrigen Scheiben verwenden
+//gibt None zurück, wenn die Größe nicht eingeschränkt ist
+fn feasible_sizes(
+    pieces_map: &PiecesMap,
+    config: &SolverConfig,
+    last: bool,
+) -> Option<FeasibleSizes> {
+    let single = (config.single_cheese || last) && !config.find_missing;
+    let target = config.target.map(|mut target| {
+        target.sort_unstable_by(|a, b| b.cmp(a));
+        target
+    });
+    let mut candidates = match (single, config.target_volume, target) {
+        (true, _, _) => candidate_sizes(pieces_map, None, false),
+        (false, Some(volume), _) => candidate_sizes(pieces_map, Some(volume), config.find_missing),
+        (false, None, Some(target)) => vec![target],
+        (false, None, None) => return None,
+    };
+    if let Some(volume) = config.target_volume {
+        candidates.retain(|size| size.iter().map(|len| *len as u64).product::<u64>() == volume);
+    }
+    if let Some(target) = target {
+        candidates.retain(|size| *size == target);
+    }
+    Some(FeasibleSizes::from_candidates(
+        pieces_map,
+        &candidates,
+        single,
+    ))
+}
+//Gibt true zurück, wenn der Käse fertig sein darf, mit feasible nur, wenn er eine der möglichen
+//Größen hat, mit find_missing und config.target reicht es, wenn er sich mit höchstens
+//max_added hinzugefügten Stücken zu ihr ergänzen lässt (siehe complete_to_target)
+fn is_complete(
+    cheese: &Cheese,
+    path: &PrevPieces,
+    feasible: Option<&FeasibleSizes>,
+    max_added: u32,
+    config: &SolverConfig,
+) -> bool {
+    let Some(feasible) = feasible else {
+        return true;
+    };
+    let sizes = feasible.sizes(&path.start_piece);
+    if config.find_missing && config.target.is_some() {
+        sizes.iter().any(|size| {
+            // Die fehlenden Seitenlängen werden erst gezählt, wenn der Käse in size passt,
+            // sonst wären sie negativ
+            let fits = cheese.size.iter().zip(size).all(|(len, max)| len <= max);
+            fits && {
+                let n_missing = size.iter().sum::<u32>() - cheese.size.iter().sum::<u32>();
+                path.n_added + n_missing <= max_added
+            }
+        })
+    } else {
+        sizes.contains(&cheese.size)
+    }
+}
 // Setzt nur einen Käse zusammen, wird immer wieder von construct_cheeses aufgerufen
+// Gibt den gefundenen Käse zurück, zusammen mit den anderen Pfaden, die im selben Schritt
+// geendet haben (nur mit config.detect_ambiguity)
+// Mit config.eager_missing gilt eine Gruppe schon als beendet, wenn ein Pfad endet, der weniger
+// hinzugefügte Stücke hat als alle Pfade, die weiterlaufen, Pfade, die alle übrigen Scheiben
+// verwenden, werden aber bevorzugt, die anderen nur verwendet, wenn kein solcher Pfad endet
 fn construct_cheese(
     mut top_paths: Vec<Vec<PossPath>>, // Die Pfade, nach Startstück getrennt
     min_path_len: usize,               // Die minimale Länge eines Pfades
-    find_missing: bool,                // Ob nach fehlenden Stücken gesucht werden soll
-) -> Option<(Cheese, PrevPieces)> {
+    max_added: u32,                    // Wie viele Stücke höchstens hinzugefügt werden dürfen
+    feasible: Option<&FeasibleSizes>, // Die möglichen Größen des Käses, falls sie eingeschränkt sind
+    config: &SolverConfig,
+    stats: &mut SearchStats,
+) -> Option<(FoundCheese, Vec<FoundCheese>)> {
     let mut i = 0; // Die aktuelle Länge der Pfade
+                   // Die bereits erreichten Zustände (nur mit config.transpositions), da die Pfade in der
+                   // Breitensuche wachsen, wird jeder Zustand zuerst mit den wenigsten hinzugefügten Stücken erreicht
+    let mut seen_states = FxHashMap::default();
+    // Nach wie vielen Schritten nur noch ein Startstück weiterverfolgt wird, bei längeren Folgen
+    // fehlender Scheiben später, da eine Folge mehrere hinzugefügte Stücke auf einmal kostet
+    let filter_step = (min_path_len / 2 * config.max_missing_run.max(1)).min(min_path_len);
+    // Der erste Käse, der mit config.eager_missing nicht alle übrigen Scheiben verwendet
+    let mut fallback = None;
     while !top_paths.is_empty() {
-        if i == min_path_len / 2 && i > 3 && find_missing {
+        let frontier = top_paths.iter().map(|sub_paths| sub_paths.len()).sum();
+        stats.peak_frontier = stats.peak_frontier.max(frontier);
+        if i == filter_step && i > 3 && config.find_missing {
             // Wenn etwas Zeit vergangen ist, wird nur der Pfad mit den wenigsten
             // hinzugefügten Stücken weiterverfolgt
-            top_paths = filter_top_paths(top_paths);
+            top_paths = filter_top_paths(top_paths, config);
+            // Die Zustände der entfernten Pfade dürfen wieder erreicht werden
+            seen_states.clear();
         }
         let mut new_top_paths = vec![];
+        // Die Pfade, die in diesem Schritt geendet haben, und welcher davon das Ergebnis ist
+        let mut ended_paths = vec![];
+        // Ob die beendeten Pfade alle übrigen Scheiben verwenden (nur mit config.eager_missing)
+        let mut ended_used_up = vec![];
+        let mut result_i = None;
         for sub_paths in top_paths {
             let mut new_paths = vec![];
-            // Der aktuelle Pfad wird gespeichert, sodass er als Ergebnis zurückgegeben werden kann,
-            // wenn keine neuen Pfade gefunden werden
-            let curr_result = {
-                let PossPath {
-                    cheese,
-                    prev_pieces: path,
-                    ..
-                } = &sub_paths[0];
-                (*cheese, path.clone())
-            };
+            // Der erste Pfad, der mit einem fertigen Käse endet, wird gespeichert, sodass er als
+            // Ergebnis zurückgegeben werden kann, wenn keine neuen Pfade gefunden werden
+            let mut curr_result = None;
+            let first_ended_i = ended_paths.len();
+            // Die wenigsten hinzugefügten Stücke eines Pfades, der weiterläuft
+            let mut min_added_continuing = u32::MAX;
             //erzeugt neue Pfade
             for poss_path in sub_paths.into_iter() {
-                let paths = poss_path.gen_new_paths(find_missing);
+                let n_added = poss_path.prev_pieces.n_added;
+                let used_up = poss_path.pieces_left.is_empty();
+                let ended_path = (poss_path.cheese, poss_path.prev_pieces.clone());
+                let complete = is_complete(
+                    &poss_path.cheese,
+                    &poss_path.prev_pieces,
+                    feasible,
+                    max_added,
+                    config,
+                );
+                let mut paths = poss_path.gen_new_paths(config);
+                // Pfade mit zu vielen hinzugefügten Stücken werden verworfen
+                let n_paths = paths.len();
+                paths.retain(|poss_path| poss_path.prev_pieces.n_added <= max_added);
+                stats.n_over_budget += n_paths - paths.len();
+                stats.n_infeasible += retain_feasible(&mut paths, feasible);
+                if paths.is_empty() && !complete {
+                    // Der Käse hat keine der möglichen Größen
+                    stats.n_infeasible += 1;
+                } else if paths.is_empty() {
+                    if config.detect_ambiguity || config.eager_missing {
+                        ended_paths.push(ended_path.clone());
+                        ended_used_up.push(used_up);
+                    }
+                    curr_result.get_or_insert((ended_path, used_up));
+                } else {
+                    min_added_continuing = min_added_continuing.min(n_added);
+                }
+                stats.n_paths += paths.len();
                 new_paths.extend(paths);
             }
             // Entfernt Pfade mit mehr hinzugefügten Stücken
             if new_paths.len() > 1 {
-                new_paths = filter_sub_paths(new_paths);
+                new_paths = filter_sub_paths(new_paths, config);
+            }
+            // Pfade mit einem Zustand, der schon erreicht wurde, werden nicht weiterverfolgt
+            let mut merged_any = false;
+            if config.transpositions {
+                let n_paths = new_paths.len();
+                new_paths.retain(|poss_path| {
+                    poss_path.insert_state(&mut seen_states, poss_path.state())
+                });
+                stats.n_transpositions += n_paths - new_paths.len();
+                merged_any = new_paths.len() < n_paths;
             }
-            if new_paths.is_empty() {
+            // Der erste Pfad, der in diesem Schritt geendet hat und weniger hinzugefügte Stücke
+            // hat als alle Pfade, die weiterlaufen
+            let cheapest_ended = (config.eager_missing && i >= min_path_len)
+                .then(|| {
+                    ended_paths[first_ended_i..]
+                        .iter()
+                        .position(|(_, path)| path.n_added < min_added_continuing)
+                })
+                .flatten()
+                .map(|j| first_ended_i + j);
+            if new_paths.is_empty() && (merged_any || curr_result.is_none()) {
+                // Die Pfade dieser Gruppe werden in einer anderen Gruppe fortgesetzt
+                // oder führen zu keinem fertigen Käse
+            } else if new_paths.is_empty() {
                 if i >= min_path_len {
-                    return Some(curr_result);
+                    let (result, used_up) = curr_result.unwrap();
+                    if config.eager_missing && !used_up {
+                        // Ein Käse, der alle übrigen Scheiben verwendet, wird bevorzugt
+                        fallback.get_or_insert(result);
+                        continue;
+                    }
+                    if !config.detect_ambiguity {
+                        return Some((result, vec![]));
+                    }
+                    // Die restlichen Pfade dieses Schritts werden noch zu Ende erzeugt,
+                    // um zu sehen, ob sie auch enden
+                    result_i.get_or_insert(first_ended_i);
                 }
+            } else if let Some(cheapest_ended) = cheapest_ended {
+                if !ended_used_up[cheapest_ended] {
+                    fallback.get_or_insert_with(|| ended_paths[cheapest_ended].clone());
+                    new_top_paths.push(new_paths);
+                    continue;
+                }
+                if !config.detect_ambiguity {
+                    return Some((ended_paths.swap_remove(cheapest_ended), vec![]));
+                }
+                result_i.get_or_insert(cheapest_ended);
             } else {
                 // Entfernt gleiche Pfade
                 new_top_paths.push(new_paths);
             }
         }
+        if let Some(result_i) = result_i {
+            let result = ended_paths.remove(result_i);
+            // Nur Pfade mit höchstens so vielen hinzugefügten Scheiben sind gleich gute Lösungen
+            ended_paths.retain(|(_, path)| path.n_added <= result.1.n_added);
+            return Some((result, ended_paths));
+        }
         top_paths = new_top_paths;
         i += 1;
     }
-    None
+    fallback.map(|fallback| (fallback, vec![]))
+}
+//Vergleicht die gewählte Lösung mit den anderen Pfaden, die gleichzeitig geendet haben
+fn find_ambiguity(
+    cheese_i: usize,
+    chosen: &Solution,
+    alternatives: impl IntoIterator<Item = Solution>,
+) -> Option<Ambiguity> {
+    let mut solutions: Vec<Solution> = vec![];
+    for solution in alternatives {
+        if &solution != chosen && !solutions.contains(&solution) {
+            solutions.push(solution);
+        }
+    }
+    let alternatives = solutions;
+    let n_solutions = alternatives.len() + 1;
+    let alternative = alternatives.into_iter().next()?;
+    let diverges_at = chosen
+        .pieces
+        .iter()
+        .zip(&alternative.pieces)
+        .position(|(a, b)| a != b)
+        .unwrap_or(chosen.pieces.len().min(alternative.pieces.len()));
+    Some(Ambiguity {
+        cheese_i,
+        n_solutions,
+        alternative,
+        diverges_at,
+    })
+}
+///Sucht unter allen Reihenfolgen der echten Scheiben von chosen (siehe enumerate_cheeses) eine
+///andere Lösung mit höchstens so vielen hinzugefügten Scheiben wie chosen
+///Anders als bei config.detect_ambiguity ist chosen eindeutig, wenn None zurückgegeben wird,
+///dafür werden alle Reihenfolgen durchsucht, was bei vielen Scheiben sehr lange dauern kann
+pub fn find_alternative(
+    cheese_i: usize,
+    chosen: &Solution,
+    config: &SolverConfig,
+) -> Option<Ambiguity> {
+    let mut counts = FxHashMap::default();
+    for piece in chosen.pieces.iter().filter(|piece| !piece.is_added) {
+        *counts.entry(piece.piece).or_insert(0) += 1;
+    }
+    let n_pieces = counts.values().sum::<u32>() as usize;
+    let n_added = (chosen.pieces.len() - n_pieces) as u32;
+    let config = SolverConfig {
+        max_missing: Some(n_added),
+        max_missing_total: None,
+        detect_ambiguity: false,
+        exact: false,
+        ..*config
+    };
+    let pieces = Box::new(PiecesMap::new_from_map(counts));
+    let alternative =
+        enumerate_cheeses(pieces, n_pieces, &config).find(|solution| solution != chosen);
+    find_ambiguity(cheese_i, chosen, alternative)
+}
+//Wählt die Startscheiben aus, die Bedingungen gelten für jeden Käse:
+//- mit extendable muss der Käse mehr als eine Scheibe haben, an den Startblock start×1
+//  muss also eine andere Scheibe passen (start selbst, start.0×1 oder start.1×1)
+//- mit single stammen alle Scheiben von einem Käse, jede Scheibe enthält eine der beiden Seiten
+//  des Käses, die mindestens so lang wie die Startscheibe sind, ihre längere Seite ist also
+//  mindestens so lang wie die kürzere Seite der Startscheibe
+//- mit feasible muss die Startscheibe in eine der möglichen Größen passen
+//die übrigen Startscheiben werden nach Fläche sortiert, damit die Reihenfolge nicht von der
+//HashMap abhängt, kleine Scheiben zuerst, da die Seiten eines Käses beim Anfügen nur wachsen
+fn select_starts(
+    pieces_map: &PiecesMap,
+    extendable: bool,
+    single: bool,
+    feasible: Option<&FeasibleSizes>,
+    selection: &mut StartSelection,
+) -> Vec<Piece> {
+    let count = |piece: &Piece| *pieces_map.get(piece).unwrap_or(&0);
+    let kinds = pieces_map
+        .base
+        .keys()
+        .filter(|piece| count(piece) > 0)
+        .cloned()
+        .collect::<Vec<_>>();
+    let min_long = kinds.iter().map(|piece| piece.0).min().unwrap_or(0);
+    let mut starts = vec![];
+    for start in kinds {
+        // Wie viele Scheiben dieser Größe übrig sind, wenn die Startscheibe verwendet wurde
+        let n_other = |piece: Piece| count(&piece) - (piece == start) as u32;
+        let can_extend = [start, Piece::new(start.0, 1), Piece::new(start.1, 1)]
+            .into_iter()
+            .any(|piece| n_other(piece) > 0);
+        if feasible.is_some_and(|feasible| feasible.sizes(&start).is_empty()) {
+            selection.n_infeasible += 1;
+        } else if single && start.1 > min_long {
+            selection.n_too_thick += 1;
+        } else if extendable && !can_extend {
+            selection.n_unextendable += 1;
+        } else {
+            starts.push(start);
+        }
+    }
+    starts.sort_unstable_by_key(|piece| (piece.0 as u64 * piece.1 as u64, piece.0, piece.1));
+    selection.n_tried += starts.len();
+    starts
+}
+//Erzeugt die Startpfade für die Startscheiben starts, nach Startstück getrennt
+//mit config.max_missing_start gibt es für jedes Startstück zusätzlich Pfade,
+//die mit 1 bis max_missing_start fehlenden Scheiben derselben Größe beginnen
+fn start_paths(
+    pieces_map: &PiecesMap,
+    starts: &[Piece],
+    max_added: u32,
+    config: &SolverConfig,
+) -> Vec<Vec<PossPath>> {
+    let max_missing_start = if config.find_missing {
+        config.max_missing_start.min(max_added as usize)
+    } else {
+        0
+    };
+    let mut top_paths = vec![];
+    for piece in starts {
+        let mut cheese = Cheese::new([piece.0, piece.1, 0]);
+        let mut path = PrevPieces::new(*piece);
+        for n_missing in 0..=max_missing_start {
+            if n_missing > 0 {
+                cheese = cheese.add_piece(*piece).unwrap();
+                path = path.extend_added(*piece);
+            }
+            // Die Pfade bekommen eigene Gruppen, da sie sonst wegen der
+            // hinzugefügten Scheiben sofort von filter_sub_paths entfernt würden
+            let start = PossPath::new(cheese, path.clone(), Box::new(pieces_map.clone()));
+            top_paths.push(vec![start]);
+        }
+    }
+    top_paths
+}
+//Ergänzt fehlende äußere Scheiben, bis der Käse die Größe target hat
+//passt der Käse nicht in target oder wären mehr als max_added Stücke hinzugefügt,
+//bleibt er unverändert
+fn complete_to_target(
+    mut cheese: Cheese,
+    mut path: PrevPieces,
+    target: [u32; 3],
+    max_added: u32,
+) -> (Cheese, PrevPieces) {
+    let mut target = target;
+    target.sort_unstable_by(|a, b| b.cmp(a));
+    // Da beide Größen absteigend sortiert sind, lässt sich der Käse genau dann
+    // zu target vergrößern, wenn jede Seitenlänge höchstens so groß ist
+    if cheese.size.iter().zip(&target).any(|(len, max)| len > max) {
+        return (cheese, path);
+    }
+    let n_missing = target
+        .iter()
+        .zip(&cheese.size)
+        .map(|(max, len)| max - len)
+        .sum::<u32>();
+    if path.n_added + n_missing > max_added {
+        return (cheese, path);
+    }
+    while let Some(i) = (0..3).position(|i| cheese.size[i] < target[i]) {
+        let piece = cheese.get_sides()[i];
+        cheese = cheese.add_piece(piece).unwrap();
+        path = path.extend_added(piece);
+    }
+    (cheese, path)
 }
 /// Findet alle möglichen Käse
 pub fn construct_cheeses(
@@ -105,37 +589,77 @@ pub fn construct_cheeses(
     // Anzahl der Stücke, wird benötigt da gleiche Stücke
     // im Pieces-Objekt zusammengefasst werden
     n_pieces: usize,
-    find_missing: bool,
+    config: &SolverConfig,
+    stats: &mut SearchStats,
 ) -> Vec<(Cheese, PrevPieces)> {
+    if config.exact {
+        return partition_cheeses(&pieces, n_pieces, config, stats);
+    }
     let mut pieces_map = pieces;
     // Die gefundenen Käse
     let mut results = vec![];
     // Die Stücke, die bereits verwendet wurden
     // Wird verwendet um zu überprüfen ob alle Stücke verwendet wurden
     let mut used_pieces = vec![];
+    // Wie viele Stücke insgesamt hinzugefügt wurden
+    let mut n_added_total = 0;
     let mut min_path_len = n_pieces * 3 / 4; // Die minimale Länge eines Pfades
                                              // Es wird davon ausgegangen, dass ein Käse mindestens 3 Stücke hat
     while min_path_len > (n_pieces - used_pieces.len()) / 5 {
         // Die Stücke, die noch nicht verwendet wurden
         // werden als Startstücke verwendet
-        let keys = pieces_map.base.keys().cloned().collect::<Vec<Piece>>();
-        let top_paths = keys
-            .iter()
-            .map(|piece| {
-                let cheese = Cheese::new([piece.0, piece.1, 0]);
-                let start = PossPath::new(cheese, PrevPieces::new(*piece), pieces_map.clone());
-                vec![start]
-            })
-            .collect::<Vec<_>>();
+        // Wie viele Stücke dieser Käse noch hinzufügen darf
+        let max_added = config.max_missing.unwrap_or(u32::MAX).min(
+            config
+                .max_missing_total
+                .map_or(u32::MAX, |total| total.saturating_sub(n_added_total)),
+        );
+        // Die möglichen Größen, z.B. wenn alle Scheiben von einem Käse stammen
+        let feasible = feasible_sizes(&pieces_map, config, false);
+        // Käse mit nur einer Scheibe werden erst ab einer Mindestlänge von 1 gefunden,
+        // fehlende Scheiben können auch an Startscheiben passen, an die sonst nichts passt
+        let starts = select_starts(
+            &pieces_map,
+            min_path_len > 1 && !config.find_missing,
+            config.single_cheese,
+            feasible.as_ref(),
+            &mut stats.starts,
+        );
+        let top_paths = start_paths(&pieces_map, &starts, max_added, config);
         // Es wird versucht einen Käse zu finden
-        if let Some((cheese, path)) = construct_cheese(top_paths, min_path_len, find_missing) {
+        if let Some(((cheese, path), alternatives)) = construct_cheese(
+            top_paths,
+            min_path_len,
+            max_added,
+            feasible.as_ref(),
+            config,
+            stats,
+        ) {
+            if !alternatives.is_empty() {
+                let chosen = Solution::from_path(cheese, &path);
+                stats.ambiguities.extend(find_ambiguity(
+                    results.len(),
+                    &chosen,
+                    alternatives
+                        .iter()
+                        .map(|(cheese, path)| Solution::from_path(*cheese, path)),
+                ));
+            }
             // Wenn ein Käse gefunden wurde, werden die Stücke aus dem Pieces-Objekt entfernt
             let new_used_pieces = path.curr.get_real_pieces();
-            pieces_map = Box::new(pieces_map.clone_without(&new_used_pieces));
+            pieces_map = pieces_map.clone_without(&new_used_pieces).into();
             used_pieces.extend(new_used_pieces);
 
             // Die minimale Pfadlänge wird angepasst
             min_path_len = (n_pieces - used_pieces.len()) * 3 / 4;
+            // Fehlende äußere Scheiben werden bis zur bekannten Größe ergänzt
+            let (cheese, path) = match config.target {
+                Some(target) if config.find_missing => {
+                    complete_to_target(cheese, path, target, max_added)
+                }
+                _ => (cheese, path),
+            };
+            n_added_total += path.n_added;
             results.push((cheese, path));
             // found_cheese = true;
         } else {
@@ -147,6 +671,15 @@ pub fn construct_cheeses(
     if !results.is_empty() {
         // Es wird überprüft ob alle Stücke verwendet wurden
         match used_pieces.len().cmp(&n_pieces) {
+            // Wurden Pfade wegen zu vieler hinzugefügter Stücke oder einer nicht möglichen Größe
+            // verworfen, gibt es mit diesen Einschränkungen keine Lösung
+            Ordering::Less
+                if stats.n_over_budget > 0
+                    || stats.n_infeasible > 0
+                    || stats.starts.n_infeasible > 0 =>
+            {
+                return vec![]
+            }
             Ordering::Less => {
                 panic!(
                     "not all pieces used!! pieces left: {}",
@@ -161,3 +694,526 @@ pub fn construct_cheeses(
     }
     results
 }
+//Ein Schritt der Tiefensuche in Partition::split
+enum Step {
+    Extend(PossPath),           //der Pfad wird fortgesetzt
+    Finish(Cheese, PrevPieces), //der Käse wird hier beendet
+}
+//Der Zustand der exakten Zerlegung (siehe partition_cheeses)
+struct Partition<'a> {
+    config: &'a SolverConfig,
+    //die Zustände, in denen sich die übrigen Scheiben nicht zerlegen ließen,
+    //als übrige Scheiben (siehe PiecesMap::counts) und Anzahl der Stücke, die noch hinzugefügt
+    //werden dürfen, zusammen mit der größten Anzahl an Käsen, mit der es nicht geklappt hat
+    failed: FxHashMap<(Vec<(Piece, u32)>, u32), usize>,
+    //die fertigen Käse, der zuletzt gefundene zuerst
+    found: Vec<FoundCheese>,
+}
+
+impl Partition<'_> {
+    //Versucht die übrigen Scheiben in höchstens max_cheeses Käse zu zerlegen,
+    //die zusammen höchstens budget hinzugefügte Stücke haben
+    fn split(
+        &mut self,
+        pieces_map: &PiecesMap,
+        n_left: usize,
+        budget: u32,
+        max_cheeses: usize,
+        stats: &mut SearchStats,
+    ) -> bool {
+        if n_left == 0 {
+            return true;
+        }
+        if max_cheeses == 0 {
+            return false;
+        }
+        let state = (pieces_map.counts(), budget);
+        if self.failed.get(&state).is_some_and(|n| *n >= max_cheeses) {
+            return false;
+        }
+        let config = self.config;
+        let max_added = config.max_missing.unwrap_or(u32::MAX).min(budget);
+        // Die größte übrige Scheibe gehört zu einem der Käse, deshalb werden nur Käse mit ihr beendet,
+        // sodass die Käse einer Zerlegung nicht in jeder Reihenfolge ausprobiert werden
+        let largest = *pieces_map
+            .base
+            .keys()
+            .max_by_key(|piece| (piece.0 as u64 * piece.1 as u64, piece.0, piece.1))
+            .unwrap();
+        // Der letzte Käse muss alle übrigen Scheiben verwenden
+        let feasible = feasible_sizes(pieces_map, config, max_cheeses == 1);
+        // Käse aus einer Scheibe sind hier erlaubt
+        let starts = select_starts(
+            pieces_map,
+            false,
+            config.single_cheese || max_cheeses == 1,
+            feasible.as_ref(),
+            &mut stats.starts,
+        );
+        let mut stack = start_paths(pieces_map, &starts, max_added, config)
+            .into_iter()
+            .flatten()
+            .map(Step::Extend)
+            .collect::<Vec<_>>();
+        stack.reverse();
+        // Die schon fortgesetzten Zustände, ein Zustand wird nur einmal fortgesetzt,
+        // da er beim ersten Mal nicht zu einer Zerlegung geführt hat
+        let mut seen_states = FxHashMap::default();
+        while let Some(step) = stack.pop() {
+            stats.peak_frontier = stats.peak_frontier.max(stack.len() + 1);
+            match step {
+                Step::Extend(poss_path) => {
+                    let path = &poss_path.prev_pieces;
+                    let (size, used, run_added) = poss_path.state();
+                    let state = (size, used, run_added, path.n_added);
+                    if !poss_path.insert_state(&mut seen_states, state) {
+                        continue;
+                    }
+                    // Ein Käse endet nicht mit hinzugefügten Stücken,
+                    // da er ohne sie mit weniger hinzugefügten Stücken enden würde
+                    if path.len > 1 && path.run_added == 0 {
+                        stack.push(Step::Finish(poss_path.cheese, path.clone()));
+                    }
+                    let mut paths = poss_path.gen_new_paths(config);
+                    let n_paths = paths.len();
+                    paths.retain(|poss_path| poss_path.prev_pieces.n_added <= max_added);
+                    stats.n_over_budget += n_paths - paths.len();
+                    stats.n_infeasible += retain_feasible(&mut paths, feasible.as_ref());
+                    stats.n_paths += paths.len();
+                    // Umgekehrt, damit der erste neue Pfad als nächstes fortgesetzt wird,
+                    // der Käse wird erst beendet, wenn alle längeren Käse ausprobiert wurden
+                    stack.extend(paths.into_iter().rev().map(Step::Extend));
+                }
+                Step::Finish(cheese, path) => {
+                    let n_used = path.len - 1;
+                    if max_cheeses == 1 && n_used < n_left
+                        || !is_complete(&cheese, &path, feasible.as_ref(), max_added, config)
+                    {
+                        continue;
+                    }
+                    let used = path.curr.get_real_pieces();
+                    if !used.contains(&largest) {
+                        continue;
+                    }
+                    let rest = pieces_map.clone_without(&used);
+                    if self.split(
+                        &rest,
+                        n_left - n_used,
+                        budget - path.n_added,
+                        max_cheeses - 1,
+                        stats,
+                    ) {
+                        self.found.push((cheese, path));
+                        return true;
+                    }
+                    stats.n_backtracks += 1;
+                }
+            }
+        }
+        self.failed.insert(state, max_cheeses);
+        false
+    }
+}
+// Zerlegt die Scheiben mit Backtracking in möglichst wenige Käse, sodass jede Scheibe verwendet wird
+// Es wird zuerst versucht, alle Scheiben in einen Käse zusammenzusetzen, dann in zwei usw.,
+// bis höchstens config.max_cheeses Käse, gibt es keine solche Zerlegung, ist das Ergebnis leer
+// Mit find_missing wird danach mit gleich vielen Käsen so lange nach einer Zerlegung mit weniger
+// hinzugefügten Stücken gesucht, bis es keine mehr gibt, die Zerlegung hat also unter denen mit
+// den wenigsten Käsen die wenigsten hinzugefügten Stücke
+// Deutlich langsamer als die normale Suche, wenn es mehrere Käse gibt
+fn partition_cheeses(
+    pieces_map: &PiecesMap,
+    n_pieces: usize,
+    config: &SolverConfig,
+    stats: &mut SearchStats,
+) -> Vec<FoundCheese> {
+    let mut partition = Partition {
+        config,
+        failed: FxHashMap::default(),
+        found: vec![],
+    };
+    let max_cheeses = config.max_cheeses.unwrap_or(n_pieces);
+    let budget = config.max_missing_total.unwrap_or(u32::MAX);
+    let Some(n_cheeses) = (1..=max_cheeses)
+        .find(|n_cheeses| partition.split(pieces_map, n_pieces, budget, *n_cheeses, stats))
+    else {
+        return vec![];
+    };
+    let mut results = std::mem::take(&mut partition.found);
+    loop {
+        let n_added = results.iter().map(|(_, path)| path.n_added).sum::<u32>();
+        if n_added == 0 || !partition.split(pieces_map, n_pieces, n_added - 1, n_cheeses, stats) {
+            break;
+        }
+        results = std::mem::take(&mut partition.found);
+    }
+    results.reverse();
+    // Fehlende äußere Scheiben werden bis zur bekannten Größe ergänzt
+    let Some(target) = config.target.filter(|_| config.find_missing) else {
+        return results;
+    };
+    let mut n_added_total = results.iter().map(|(_, path)| path.n_added).sum::<u32>();
+    results
+        .into_iter()
+        .map(|(cheese, path)| {
+            let n_added = path.n_added;
+            let max_added = config.max_missing.unwrap_or(u32::MAX).min(
+                config.max_missing_total.map_or(u32::MAX, |total| {
+                    total.saturating_sub(n_added_total - n_added)
+                }),
+            );
+            let (cheese, path) = complete_to_target(cheese, path, target, max_added);
+            n_added_total += path.n_added - n_added;
+            (cheese, path)
+        })
+        .collect()
+}
+///Zählt alle Scheibenreihenfolgen auf, die alle Scheiben zu einem einzigen Käse zusammensetzen
+///die Lösungen werden nacheinander erzeugt, erst beim Abrufen wird weitergesucht,
+///sodass z.B. mit take() nur die ersten Lösungen gesucht werden
+pub struct Reconstructions {
+    stack: Vec<PossPath>, //die Pfade, die noch fortgesetzt werden müssen (Tiefensuche)
+    n_pieces: usize,
+    max_added: u32,
+    //die möglichen Größen des Käses, falls sie eingeschränkt sind
+    feasible: Option<FeasibleSizes>,
+    config: SolverConfig,
+    //die schon zurückgegebenen Reihenfolgen
+    found: FxHashSet<Vec<SolutionPiece>>,
+}
+
+impl Iterator for Reconstructions {
+    type Item = Solution;
+    fn next(&mut self) -> Option<Solution> {
+        while let Some(poss_path) = self.stack.pop() {
+            let (cheese, path) = (poss_path.cheese, poss_path.prev_pieces.clone());
+            let mut paths = poss_path.gen_new_paths(&self.config);
+            paths.retain(|poss_path| poss_path.prev_pieces.n_added <= self.max_added);
+            retain_feasible(&mut paths, self.feasible.as_ref());
+            if paths.is_empty() {
+                // Nur Pfade, die alle Scheiben verwenden, sind Lösungen
+                if path.curr.get_real_pieces().len() == self.n_pieces
+                    && is_complete(
+                        &cheese,
+                        &path,
+                        self.feasible.as_ref(),
+                        self.max_added,
+                        &self.config,
+                    )
+                {
+                    // Fehlende äußere Scheiben werden wie bei construct_cheeses ergänzt
+                    let (cheese, path) = match self.config.target {
+                        Some(target) if self.config.find_missing => {
+                            complete_to_target(cheese, path, target, self.max_added)
+                        }
+                        _ => (cheese, path),
+                    };
+                    let solution = Solution::from_path(cheese, &path);
+                    // Eine Reihenfolge kann auf mehreren Wegen entstehen, z.B. wenn eine fehlende
+                    // Scheibe an verschiedenen Seiten gleicher Größe vermutet wird
+                    if self.found.insert(solution.pieces.clone()) {
+                        return Some(solution);
+                    }
+                }
+            } else {
+                // Umgekehrt, damit der erste neue Pfad als nächstes fortgesetzt wird
+                self.stack.extend(paths.into_iter().rev());
+            }
+        }
+        None
+    }
+}
+///Zählt alle Lösungen auf, die alle Scheiben zu einem einzigen Käse zusammensetzen (siehe Reconstructions)
+///gleiche Scheiben werden nur gezählt und gleiche Seiten des Käses nur einmal verwendet,
+///Reihenfolgen, die trotzdem auf mehreren Wegen gefunden werden, werden nur einmal zurückgegeben
+///mit find_missing werden auch Lösungen mit fehlenden Scheiben aufgezählt,
+///höchstens config.max_missing bzw. config.max_missing_total viele
+pub fn enumerate_cheeses(
+    pieces: Box<PiecesMap>,
+    n_pieces: usize,
+    config: &SolverConfig,
+) -> Reconstructions {
+    let max_added = config
+        .max_missing
+        .unwrap_or(u32::MAX)
+        .min(config.max_missing_total.unwrap_or(u32::MAX));
+    let feasible = feasible_sizes(&pieces, config, true);
+    let starts = select_starts(
+        &pieces,
+        n_pieces > 1 && !config.find_missing,
+        true,
+        feasible.as_ref(),
+        &mut StartSelection::default(),
+    );
+    let mut stack = start_paths(&pieces, &starts, max_added, config)
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    stack.reverse();
+    Reconstructions {
+        stack,
+        n_pieces,
+        max_added,
+        feasible,
+        config: *config,
+        found: FxHashSet::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // der Käse 10×8×6 in der Reihenfolge, in der die Scheiben angefügt werden
+    const ORDER: &[(u32, u32)] = &[
+        (4, 2),
+        (4, 2),
+        (4, 2),
+        (3, 2),
+        (3, 2),
+        (6, 3),
+        (6, 3),
+        (6, 4),
+        (6, 4),
+        (6, 5),
+        (6, 5),
+        (6, 5),
+        (6, 5),
+        (8, 6),
+        (6, 6),
+        (9, 6),
+        (9, 6),
+        (8, 6),
+    ];
+
+    // die Reihenfolge, in der die Scheiben an den Stellen eaten fehlen
+    fn eaten_order(order: &[(u32, u32)], eaten: &[usize]) -> Vec<SolutionPiece> {
+        order
+            .iter()
+            .enumerate()
+            .map(|(i, (a, b))| SolutionPiece {
+                piece: Piece(*a, *b),
+                is_added: eaten.contains(&i),
+            })
+            .collect()
+    }
+    fn pile(order: &[SolutionPiece]) -> Vec<Piece> {
+        order
+            .iter()
+            .filter(|piece| !piece.is_added)
+            .map(|piece| piece.piece)
+            .collect()
+    }
+    fn solve(pieces: &[Piece], config: &SolverConfig) -> Vec<Solution> {
+        let pieces_map = Box::new(PiecesMap::new(&pieces.to_vec()));
+        construct_cheeses(
+            pieces_map,
+            pieces.len(),
+            config,
+            &mut SearchStats::default(),
+        )
+        .iter()
+        .map(|(cheese, path)| Solution::from_path(*cheese, path))
+        .collect()
+    }
+
+    #[test]
+    fn recovers_run_of_two_missing_pieces() {
+        let order = eaten_order(ORDER, &[15, 16]);
+        let config = SolverConfig {
+            find_missing: true,
+            max_missing_run: 2,
+            ..SolverConfig::default()
+        };
+        let found = solve(&pile(&order), &config);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].cheese.size, [10, 8, 6]);
+        assert_eq!(found[0].pieces, order);
+    }
+
+    #[test]
+    fn recovers_single_missing_piece() {
+        let order = eaten_order(ORDER, &[7]);
+        let config = SolverConfig {
+            find_missing: true,
+            ..SolverConfig::default()
+        };
+        let found = solve(&pile(&order), &config);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].cheese.size, [10, 8, 6]);
+        assert_eq!(found[0].pieces.iter().filter(|p| p.is_added).count(), 1);
+    }
+
+    #[test]
+    fn eager_uses_up_pile_of_one_cheese() {
+        // ein anderer Käse 10×8×6, bei dem die Pfade vom Startstück 5×4 aus anfangs keine
+        // Scheiben hinzufügen, aber die inneren Scheiben übrig lassen
+        let order = eaten_order(
+            &[
+                (4, 2),
+                (4, 1),
+                (4, 3),
+                (4, 2),
+                (4, 2),
+                (5, 4),
+                (5, 4),
+                (5, 4),
+                (5, 4),
+                (5, 4),
+                (5, 4),
+                (8, 5),
+                (8, 5),
+                (8, 5),
+                (8, 7),
+                (7, 6),
+                (9, 6),
+                (8, 6),
+            ],
+            &[4],
+        );
+        let config = SolverConfig {
+            find_missing: true,
+            eager_missing: true,
+            ..SolverConfig::default()
+        };
+        let found = solve(&pile(&order), &config);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].cheese.size, [10, 8, 6]);
+    }
+
+    #[test]
+    fn enumerates_each_order_once() {
+        // der Käse 5×4×3, bei dem eine von drei gleichen Scheiben 2×2 fehlt
+        let order = eaten_order(
+            &[
+                (2, 2),
+                (2, 2),
+                (2, 2),
+                (3, 2),
+                (3, 3),
+                (3, 3),
+                (3, 3),
+                (5, 3),
+            ],
+            &[2],
+        );
+        let pieces = pile(&order);
+        let config = SolverConfig {
+            find_missing: true,
+            max_missing: Some(2),
+            ..SolverConfig::default()
+        };
+        let found = enumerate_cheeses(Box::new(PiecesMap::new(&pieces)), pieces.len(), &config)
+            .collect::<Vec<_>>();
+        assert!(found.contains(&Solution::from_pieces(order).unwrap()));
+        for (i, solution) in found.iter().enumerate() {
+            assert!(!found[i + 1..].contains(solution));
+        }
+    }
+
+    #[test]
+    fn finds_alternative_order() {
+        // 2 × 2 1, 2 2 ergibt 2×2×2, 2 2, 2 × 2 1 dagegen 4×2×1
+        let order = eaten_order(&[(2, 1), (2, 1), (2, 2)], &[]);
+        let chosen = Solution::from_pieces(order).unwrap();
+        let config = SolverConfig::default();
+        let ambiguity = find_alternative(0, &chosen, &config).unwrap();
+        assert_eq!(ambiguity.alternative.cheese.size, [4, 2, 1]);
+        // 3 × 3 2, 3 3 lässt sich nur zu 3×3×3 stapeln
+        let order = eaten_order(&[(3, 2), (3, 2), (3, 2), (3, 3)], &[]);
+        let chosen = Solution::from_pieces(order).unwrap();
+        assert!(find_alternative(0, &chosen, &config).is_none());
+    }
+
+    #[test]
+    fn merges_only_paths_with_same_pieces() {
+        let pieces = PiecesMap::new(&vec![Piece(2, 1), Piece(2, 1), Piece(2, 2), Piece(3, 2)]);
+        let path = |used: &[(Piece, bool)]| {
+            let mut prev_pieces = PrevPieces::new(Piece(2, 2));
+            for (piece, is_added) in used {
+                prev_pieces = if *is_added {
+                    prev_pieces.extend_added(*piece)
+                } else {
+                    prev_pieces.extend_real(*piece)
+                };
+            }
+            PossPath::new(
+                Cheese::new([3, 2, 2]),
+                prev_pieces,
+                Box::new(pieces.clone()),
+            )
+        };
+        let (a, b) = ((Piece(2, 2), false), (Piece(2, 1), false));
+        // derselbe Zustand wird für alle Pfade erzwungen, als hätten ihre Fingerabdrücke kollidiert
+        let mut seen = FxHashMap::default();
+        assert!(path(&[a, b]).insert_state(&mut seen, ()));
+        assert!(!path(&[b, a]).insert_state(&mut seen, ()));
+        assert!(!path(&[b, (Piece(3, 2), true), a]).insert_state(&mut seen, ()));
+        assert!(path(&[b, b]).insert_state(&mut seen, ()));
+    }
+
+    #[test]
+    fn exact_finds_fewest_added_pieces() {
+        // die erste gefundene Zerlegung ist ein Käse 14×8×6 mit 6 hinzugefügten Stücken
+        let order = eaten_order(
+            &[
+                (4, 2),
+                (4, 1),
+                (4, 1),
+                (4, 1),
+                (5, 4),
+                (5, 4),
+                (5, 4),
+                (5, 4),
+                (5, 5),
+                (5, 5),
+                (6, 5),
+                (6, 6),
+                (6, 6),
+                (7, 6),
+                (7, 6),
+                (8, 6),
+                (8, 6),
+                (8, 6),
+            ],
+            &[10],
+        );
+        let config = SolverConfig {
+            exact: true,
+            find_missing: true,
+            ..SolverConfig::default()
+        };
+        let found = solve(&pile(&order), &config);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].pieces, order);
+    }
+
+    #[test]
+    fn exact_splits_into_fewest_cheeses() {
+        // die Scheiben zweier Käse 2×2×2 und 3×2×1, aus allen Scheiben lässt sich kein Käse bauen
+        let pieces = [Piece(2, 2), Piece(2, 2), Piece(3, 2)];
+        let config = SolverConfig {
+            exact: true,
+            ..SolverConfig::default()
+        };
+        let mut sizes = solve(&pieces, &config)
+            .iter()
+            .map(|solution| solution.cheese.size)
+            .collect::<Vec<_>>();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![[2, 2, 2], [3, 2, 1]]);
+    }
+
+    #[test]
+    fn cheese_larger_than_target_is_not_complete() {
+        // der Käse 5×5×3 ist dicker als die Zielgröße und darf nicht mit ihr verglichen werden
+        let pieces = [Piece(5, 5), Piece(5, 5), Piece(5, 1)];
+        let config = SolverConfig {
+            find_missing: true,
+            max_missing_start: 3,
+            target: Some([5, 5, 2]),
+            ..SolverConfig::default()
+        };
+        assert!(solve(&pieces, &config).is_empty());
+    }
+}
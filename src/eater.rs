@@ -0,0 +1,194 @@
+use std::{fmt, str::FromStr};
+
+use rand::{seq::index::sample, Rng};
+
+use crate::{
+    cheese::Piece,
+    solution::{Solution, SolutionPiece},
+};
+
+///wie Scheiben aufgegessen werden
+///die Modelle beziehen sich auf die Reihenfolge der Scheiben,
+///z.B. die Reihenfolge einer Lösung, in der sie an den Käse angefügt werden
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EatModel {
+    ///jede Scheibe wird mit Wahrscheinlichkeit prob gegessen,
+    ///aber nie zwei direkt hintereinander (wie bisher bei --eat-prob)
+    Independent { prob: f64 },
+    ///mit Wahrscheinlichkeit prob beginnt an einer Scheibe ein Lauf
+    ///von k direkt aufeinanderfolgenden gegessenen Scheiben
+    Runs { prob: f64, k: usize },
+    ///es werden nur Scheiben von einer zufällig gewählten Seite des Käses gegessen,
+    ///jede davon mit Wahrscheinlichkeit prob
+    OneFace { prob: f64 },
+    ///genau n zufällige Scheiben werden gegessen
+    Exactly { n: usize },
+    ///die ersten n Scheiben werden gegessen
+    Start { n: usize },
+    ///die letzten n Scheiben werden gegessen
+    End { n: usize },
+}
+
+///das Ergebnis des Essens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EatResult {
+    //die übrigen Scheiben, in der ursprünglichen Reihenfolge
+    pub pieces: Vec<Piece>,
+    //die gegessenen Scheiben mit ihrer Position in der ursprünglichen Reihenfolge
+    pub eaten: Vec<(usize, Piece)>,
+}
+
+impl EatResult {
+    ///gibt die ursprüngliche Reihenfolge zurück, in der die gegessenen Scheiben
+    ///als hypothetisch markiert sind, so wie sie in einer Lösung stehen würden
+    pub fn marked_order(&self) -> Vec<SolutionPiece> {
+        let mut order = self
+            .pieces
+            .iter()
+            .map(|piece| SolutionPiece {
+                piece: *piece,
+                is_added: false,
+            })
+            .collect::<Vec<_>>();
+        //die Positionen sind aufsteigend, deshalb stimmen sie beim Einfügen
+        for (position, piece) in &self.eaten {
+            order.insert(
+                *position,
+                SolutionPiece {
+                    piece: *piece,
+                    is_added: true,
+                },
+            );
+        }
+        order
+    }
+}
+
+impl EatModel {
+    ///bestimmt, welche Scheiben gegessen werden
+    ///gibt None zurück, falls das Modell eine gültige Scheibenreihenfolge braucht
+    ///(OneFace), pieces aber keinen Käse ergibt
+    fn choose(&self, pieces: &[Piece], rng: &mut impl Rng) -> Option<Vec<bool>> {
+        let len = pieces.len();
+        let mut eaten = vec![false; len];
+        match *self {
+            EatModel::Independent { prob } => {
+                for i in 0..len {
+                    let last_eaten = i > 0 && eaten[i - 1];
+                    //die Zufallszahl wird immer gezogen, wie bisher bei --eat-prob
+                    eaten[i] = !rng.gen_bool(1.0 - prob) && !last_eaten;
+                }
+            }
+            EatModel::Runs { prob, k } => {
+                let mut i = 0;
+                while i < len {
+                    if rng.gen_bool(prob) {
+                        //nach einem Lauf bleibt mindestens eine Scheibe übrig,
+                        //damit Läufe nicht zu einem längeren Lauf verschmelzen
+                        for is_eaten in eaten.iter_mut().skip(i).take(k) {
+                            *is_eaten = true;
+                        }
+                        i += k + 1;
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+            EatModel::OneFace { prob } => {
+                let solution = Solution::from_pieces(
+                    pieces
+                        .iter()
+                        .map(|piece| SolutionPiece {
+                            piece: *piece,
+                            is_added: false,
+                        })
+                        .collect(),
+                )?;
+                let axis = rng.gen_range(0..3);
+                let (_, placed) = solution.place_pieces();
+                for (is_eaten, piece) in eaten.iter_mut().zip(placed) {
+                    *is_eaten = piece.axis == axis && rng.gen_bool(prob);
+                }
+            }
+            EatModel::Exactly { n } => {
+                for i in sample(rng, len, n.min(len)) {
+                    eaten[i] = true;
+                }
+            }
+            EatModel::Start { n } => eaten.iter_mut().take(n).for_each(|e| *e = true),
+            EatModel::End { n } => eaten.iter_mut().rev().take(n).for_each(|e| *e = true),
+        }
+        Some(eaten)
+    }
+    ///isst Scheiben nach diesem Modell und merkt sich, welche gegessen wurden
+    pub fn eat(&self, pieces: &[Piece], rng: &mut impl Rng) -> Option<EatResult> {
+        let eaten = self.choose(pieces, rng)?;
+        let mut result = EatResult {
+            pieces: vec![],
+            eaten: vec![],
+        };
+        for (position, (piece, is_eaten)) in pieces.iter().zip(eaten).enumerate() {
+            if is_eaten {
+                result.eaten.push((position, *piece));
+            } else {
+                result.pieces.push(*piece);
+            }
+        }
+        Some(result)
+    }
+}
+
+//liest ein Modell im Format "name:parameter", z.B. "runs:0.05:3" oder "exactly:4"
+impl FromStr for EatModel {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split(':').collect::<Vec<_>>();
+        let prob = |i: usize| -> Result<f64, String> {
+            let prob: f64 = parts
+                .get(i)
+                .ok_or(format!("missing probability in '{}'", s))?
+                .parse()
+                .map_err(|err| format!("{}", err))?;
+            if (0.0..=1.0).contains(&prob) {
+                Ok(prob)
+            } else {
+                Err(format!("probability {} is not between 0 and 1", prob))
+            }
+        };
+        let count = |i: usize| -> Result<usize, String> {
+            parts
+                .get(i)
+                .ok_or(format!("missing number in '{}'", s))?
+                .parse()
+                .map_err(|err| format!("{}", err))
+        };
+        match parts[0] {
+            "independent" => Ok(EatModel::Independent { prob: prob(1)? }),
+            "runs" => Ok(EatModel::Runs {
+                prob: prob(1)?,
+                k: count(2)?,
+            }),
+            "face" => Ok(EatModel::OneFace { prob: prob(1)? }),
+            "exactly" => Ok(EatModel::Exactly { n: count(1)? }),
+            "start" => Ok(EatModel::Start { n: count(1)? }),
+            "end" => Ok(EatModel::End { n: count(1)? }),
+            name => Err(format!(
+                "unknown eat model '{}', expected independent, runs, face, exactly, start or end",
+                name
+            )),
+        }
+    }
+}
+
+impl fmt::Display for EatModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EatModel::Independent { prob } => write!(f, "independent:{}", prob),
+            EatModel::Runs { prob, k } => write!(f, "runs:{}:{}", prob, k),
+            EatModel::OneFace { prob } => write!(f, "face:{}", prob),
+            EatModel::Exactly { n } => write!(f, "exactly:{}", n),
+            EatModel::Start { n } => write!(f, "start:{}", n),
+            EatModel::End { n } => write!(f, "end:{}", n),
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    cheese_builder::{construct_cheeses, SearchStats, SolverConfig},
+    eater::EatModel,
+    generator::{generate_pile, GeneratedPile},
+    pieces_map::PiecesMap,
+    score::{score, Score},
+    solution::Solution,
+    verify::verify_sequence,
+};
+
+///die Einstellungen eines Experiments
+#[derive(Debug, Clone)]
+pub struct ExperimentConfig {
+    pub sizes: Vec<[u32; 3]>, //die Größen der erzeugten Käse
+    pub eat_probs: Vec<f64>,  //mit welcher Wahrscheinlichkeit Scheiben gegessen werden
+    pub runs: usize,          //wie viele Instanzen pro Kombination gelöst werden
+    pub seed: u64,            //Startwert für die Zufallsgeneratoren
+}
+
+///das Ergebnis einer einzelnen Instanz
+#[derive(Debug, Clone)]
+pub struct InstanceResult {
+    pub success: bool,        //wurde genau der richtige Käse gefunden?
+    pub panicked: bool,       //ist die Suche abgestürzt?
+    pub runtime: Duration,    //wie lange die Suche gedauert hat
+    pub score: Option<Score>, //Vergleich der hinzugefügten Scheiben, falls ein Käse gefunden wurde
+    pub stats: SearchStats,   //Statistiken über die Suche
+}
+
+///das zusammengefasste Ergebnis für eine Kombination aus Größe und eat_prob
+#[derive(Debug, Clone)]
+pub struct ExperimentRow {
+    pub size: [u32; 3],
+    pub eat_prob: f64,
+    pub runs: usize,
+    pub n_success: usize,
+    pub n_panics: usize,
+    pub mean_runtime: Duration,
+    //Durchschnitt über alle Instanzen, in denen ein Käse gefunden wurde
+    pub mean_precision: f64,
+    pub mean_recall: f64,
+    pub mean_peak_frontier: f64,
+    pub max_peak_frontier: usize,
+}
+
+impl ExperimentRow {
+    ///fasst die Ergebnisse der Instanzen zusammen
+    fn new(size: [u32; 3], eat_prob: f64, results: &[InstanceResult]) -> Self {
+        let runs = results.len();
+        let scores = results
+            .iter()
+            .filter_map(|result| result.score.as_ref())
+            .collect::<Vec<_>>();
+        let mean = |values: Vec<f64>| {
+            if values.is_empty() {
+                0.0
+            } else {
+                values.iter().sum::<f64>() / values.len() as f64
+            }
+        };
+        Self {
+            size,
+            eat_prob,
+            runs,
+            n_success: results.iter().filter(|result| result.success).count(),
+            n_panics: results.iter().filter(|result| result.panicked).count(),
+            mean_runtime: results
+                .iter()
+                .map(|result| result.runtime)
+                .sum::<Duration>()
+                / runs.max(1) as u32,
+            mean_precision: mean(scores.iter().map(|score| score.precision()).collect()),
+            mean_recall: mean(scores.iter().map(|score| score.recall()).collect()),
+            mean_peak_frontier: mean(
+                results
+                    .iter()
+                    .map(|result| result.stats.peak_frontier as f64)
+                    .collect(),
+            ),
+            max_peak_frontier: results
+                .iter()
+                .map(|result| result.stats.peak_frontier)
+                .max()
+                .unwrap_or(0),
+        }
+    }
+    ///der Anteil der Instanzen, in denen genau der richtige Käse gefunden wurde
+    pub fn success_rate(&self) -> f64 {
+        self.n_success as f64 / self.runs.max(1) as f64
+    }
+}
+
+///löst eine erzeugte Instanz mit construct_cheeses und vergleicht das Ergebnis mit der Lösung
+///stürzt die Suche ab, wird das als Fehlschlag gezählt
+pub fn solve_instance(pile: &GeneratedPile, find_missing: bool) -> InstanceResult {
+    let pieces_map = Box::new(PiecesMap::new(&pile.pieces));
+    let config = SolverConfig {
+        find_missing,
+        ..Default::default()
+    };
+    let mut stats = SearchStats::default();
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        construct_cheeses(pieces_map, pile.pieces.len(), &config, &mut stats)
+    }));
+    let runtime = start.elapsed();
+    let Ok(result) = result else {
+        return InstanceResult {
+            success: false,
+            panicked: true,
+            runtime,
+            score: None,
+            stats,
+        };
+    };
+    let found = result
+        .iter()
+        .map(|(cheese, path)| Solution::from_path(*cheese, path))
+        .collect::<Vec<_>>();
+    //es wird nur ein Käse erzeugt, also muss auch genau einer gefunden werden
+    let (success, score) = match found.as_slice() {
+        [found] => {
+            let truth = &pile.solutions[0];
+            let sequence = found
+                .pieces
+                .iter()
+                .map(|piece| piece.piece)
+                .collect::<Vec<_>>();
+            let is_valid = verify_sequence(&sequence, Some(&pile.pieces)).is_ok();
+            (
+                is_valid && found.cheese == truth.cheese,
+                Some(score(truth, found)),
+            )
+        }
+        _ => (false, None),
+    };
+    InstanceResult {
+        success,
+        panicked: false,
+        runtime,
+        score,
+        stats,
+    }
+}
+///führt ein Experiment durch: für jede Kombination aus Größe und eat_prob werden
+///runs Instanzen erzeugt und gelöst
+///progress wird nach jeder Kombination aufgerufen
+///gibt einen Fehler zurück, wenn sich eine Instanz nicht erzeugen lässt, z.B. bei einer Größe mit 0
+pub fn run_experiment(
+    config: &ExperimentConfig,
+    mut progress: impl FnMut(&ExperimentRow),
+) -> Result<Vec<ExperimentRow>, String> {
+    let mut rows = vec![];
+    for size in &config.sizes {
+        for eat_prob in &config.eat_probs {
+            let eat_model = EatModel::Independent { prob: *eat_prob };
+            let eat_model = (*eat_prob > 0.0).then_some(&eat_model);
+            let results = (0..config.runs)
+                .map(|run| {
+                    //jede Instanz hat einen eigenen festen Startwert,
+                    //sodass bei gleicher Größe für jede eat_prob gleich geschnitten wird
+                    let mut rng = StdRng::seed_from_u64(config.seed.wrapping_add(run as u64));
+                    let pile = generate_pile(&[*size], None, eat_model, &mut rng)?;
+                    Ok(solve_instance(&pile, eat_model.is_some()))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            let row = ExperimentRow::new(*size, *eat_prob, &results);
+            progress(&row);
+            rows.push(row);
+        }
+    }
+    Ok(rows)
+}
+///schreibt die Ergebnisse eines Experiments als CSV-Datei
+pub fn write_csv(path: &str, rows: &[ExperimentRow]) {
+    let file = File::create(path).expect("couldn't create file");
+    let mut writer = BufWriter::new(file);
+    writeln!(
+        writer,
+        "a,b,c,eat_prob,runs,success_rate,panics,mean_runtime_ms,mean_precision,mean_recall,mean_peak_frontier,max_peak_frontier"
+    )
+    .expect("couldn't write csv header");
+    for row in rows {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{:.4},{},{:.4},{:.4},{:.4},{:.1},{}",
+            row.size[0],
+            row.size[1],
+            row.size[2],
+            row.eat_prob,
+            row.runs,
+            row.success_rate(),
+            row.n_panics,
+            row.mean_runtime.as_secs_f64() * 1000.0,
+            row.mean_precision,
+            row.mean_recall,
+            row.mean_peak_frontier,
+            row.max_peak_frontier
+        )
+        .expect("couldn't write csv row");
+    }
+    writer.flush().expect("couldn't flush writer");
+}
@@ -0,0 +1,225 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    cheese::Piece,
+    eater::EatModel,
+    solution::{Solution, SolutionPiece},
+};
+
+///schneidet eine Scheibe entlang der Achse axis vom Käse ab
+fn cut_slice(size: &mut [u32; 3], axis: usize) -> Piece {
+    let piece = Piece::new(size[(axis + 1) % 3], size[(axis + 2) % 3]);
+    size[axis] -= 1;
+    piece
+}
+///plant, wie oft entlang welcher Achse geschnitten wird, sodass genau n_slices Scheiben entstehen
+///gibt None zurück, falls das mit dieser Größe nicht möglich ist
+fn plan_cuts(size: [u32; 3], n_slices: usize, rng: &mut impl Rng) -> Option<Vec<usize>> {
+    //die letzte Scheibe ist der Rest des Käses, also wird einmal weniger geschnitten
+    let n_cuts = n_slices.checked_sub(1)?;
+    //Achsen, die am Ende die Dicke 1 haben können, sodass der Rest eine Scheibe ist
+    let mut end_axes = (0..3)
+        .filter(|&axis| {
+            let needed = size[axis] as usize - 1;
+            let other_max =
+                (size[(axis + 1) % 3] - 1) as usize + (size[(axis + 2) % 3] - 1) as usize;
+            needed <= n_cuts && n_cuts <= needed + other_max
+        })
+        .collect::<Vec<_>>();
+    end_axes.shuffle(rng);
+    let end_axis = *end_axes.first()?;
+    //entlang der End-Achse wird bis zur Dicke 1 geschnitten,
+    //die übrigen Schnitte werden zufällig auf die anderen beiden Achsen verteilt
+    let mut cuts = vec![end_axis; size[end_axis] as usize - 1];
+    let (a, b) = ((end_axis + 1) % 3, (end_axis + 2) % 3);
+    let mut left = [size[a] - 1, size[b] - 1];
+    for _ in cuts.len()..n_cuts {
+        let i = if left[0] == 0 {
+            1
+        } else if left[1] == 0 {
+            0
+        } else {
+            rng.gen_range(0..2)
+        };
+        left[i] -= 1;
+        cuts.push([a, b][i]);
+    }
+    cuts.shuffle(rng);
+    Some(cuts)
+}
+///simuliert das Aufschneiden eines Käses der Größe size,
+///in jedem Schritt wird eine zufällige Seite abgeschnitten, bis nur noch eine Scheibe übrig ist
+///wenn n_slices angegeben ist, entstehen genau so viele Scheiben
+///gibt die Scheiben in der Reihenfolge zurück, in der sie abgeschnitten wurden,
+///die letzte Scheibe ist der Rest des Käses
+///gibt None zurück, falls size oder n_slices nicht möglich sind
+pub fn cut_cheese(
+    size: [u32; 3],
+    n_slices: Option<usize>,
+    rng: &mut impl Rng,
+) -> Option<Vec<Piece>> {
+    if size.contains(&0) {
+        return None;
+    }
+    let mut size = size;
+    let mut pieces = vec![];
+    if let Some(n_slices) = n_slices {
+        for axis in plan_cuts(size, n_slices, rng)? {
+            pieces.push(cut_slice(&mut size, axis));
+        }
+    } else {
+        //es wird geschnitten, bis eine Seitenlänge 1 ist
+        while !size.contains(&1) {
+            let axis = rng.gen_range(0..3);
+            pieces.push(cut_slice(&mut size, axis));
+        }
+    }
+    //der Rest ist selbst eine Scheibe
+    let axis = size.iter().position(|len| *len == 1).unwrap();
+    pieces.push(cut_slice(&mut size, axis));
+    Some(pieces)
+}
+///erzeugt die Lösung zu einer Schnittreihenfolge
+///die Lösung beginnt mit der zuletzt abgeschnittenen Scheibe
+pub fn cuts_to_solution(cuts: &[Piece]) -> Solution {
+    let pieces = cuts
+        .iter()
+        .rev()
+        .map(|piece| SolutionPiece {
+            piece: *piece,
+            is_added: false,
+        })
+        .collect();
+    Solution::from_pieces(pieces).expect("cut pieces don't form a cheese")
+}
+///ein gemischter Stapel aus den Scheiben mehrerer Käse, zusammen mit der richtigen Lösung
+pub struct GeneratedPile {
+    //die gemischten Scheiben
+    pub pieces: Vec<Piece>,
+    //zu welchem Käse die Scheibe an derselben Stelle in pieces gehört
+    pub owners: Vec<usize>,
+    //die richtige Scheibenreihenfolge für jeden Käse
+    pub solutions: Vec<Solution>,
+}
+///erzeugt eine zufällige Käsegröße mit Seitenlängen zwischen 2 und max_len
+pub fn random_size(max_len: u32, rng: &mut impl Rng) -> [u32; 3] {
+    [0; 3].map(|_| rng.gen_range(2..=max_len.max(2)))
+}
+///fügt n_cheeses zufällige Käsegrößen zu sizes hinzu
+///bei share_faces hat jeder neue Käse eine Seite,
+///die genauso groß ist wie eine Seite eines vorherigen Käses,
+///sodass die Scheiben verschiedener Käse leicht verwechselt werden können
+pub fn add_random_sizes(
+    sizes: &mut Vec<[u32; 3]>,
+    n_cheeses: usize,
+    max_len: u32,
+    share_faces: bool,
+    rng: &mut impl Rng,
+) {
+    for _ in 0..n_cheeses {
+        let mut size = random_size(max_len, rng);
+        if share_faces && !sizes.is_empty() {
+            //übernimmt zwei Seitenlängen eines zufälligen vorherigen Käses
+            let other = sizes[rng.gen_range(0..sizes.len())];
+            let axis = rng.gen_range(0..3);
+            size[0] = other[(axis + 1) % 3];
+            size[1] = other[(axis + 2) % 3];
+        }
+        sizes.push(size);
+    }
+}
+///schneidet mehrere Käse auf und mischt ihre Scheiben
+///n_slices gilt für jeden Käse einzeln (siehe cut_cheese)
+///falls eat_model angegeben ist, werden von jedem Käse Scheiben gegessen,
+///diese sind in der Lösung als hypothetisch markiert und fehlen im Stapel
+///gibt einen Fehler zurück, falls einer der Käse nicht so geschnitten
+///oder nicht nach eat_model gegessen werden kann
+pub fn generate_pile(
+    sizes: &[[u32; 3]],
+    n_slices: Option<usize>,
+    eat_model: Option<&EatModel>,
+    rng: &mut impl Rng,
+) -> Result<GeneratedPile, String> {
+    let mut owned_pieces = vec![];
+    let mut solutions = vec![];
+    for (i, size) in sizes.iter().enumerate() {
+        let cuts = cut_cheese(*size, n_slices, rng).ok_or_else(|| match n_slices {
+            Some(n_slices) => format!("cheese {:?} can't be cut into {} slices", size, n_slices),
+            None => format!("cheese {:?} can't be cut", size),
+        })?;
+        let mut solution = cuts_to_solution(&cuts);
+        if let Some(eat_model) = eat_model {
+            let order = solution.real_pieces();
+            let eaten = eat_model.eat(&order, rng).ok_or_else(|| {
+                format!(
+                    "eat model {} can't eat slices of cheese {:?}",
+                    eat_model, size
+                )
+            })?;
+            solution.pieces = eaten.marked_order();
+        }
+        owned_pieces.extend(solution.real_pieces().into_iter().map(|piece| (piece, i)));
+        solutions.push(solution);
+    }
+    owned_pieces.shuffle(rng);
+    let (pieces, owners) = owned_pieces.into_iter().unzip();
+    Ok(GeneratedPile {
+        pieces,
+        owners,
+        solutions,
+    })
+}
+///schreibt zu jeder Scheibe des Stapels, zu welchem Käse sie gehört,
+///eine Zeile "w h käse", in der ersten Zeile steht die Anzahl der Scheiben
+pub fn write_owners(path: &str, pile: &GeneratedPile) {
+    let file = File::create(path).expect("couldn't create file");
+    let mut writer = BufWriter::new(file);
+    writeln!(writer, "{}", pile.pieces.len()).expect("couldn't write number of pieces");
+    for (piece, owner) in pile.pieces.iter().zip(&pile.owners) {
+        writeln!(writer, "{} {} {}", piece.0, piece.1, owner).expect("couldn't write piece");
+    }
+    writer.flush().expect("couldn't flush writer");
+}
+///liest eine mit write_owners geschriebene Datei
+///gibt die Scheiben zusammen mit dem Käse zurück, zu dem sie gehören
+pub fn read_owners(path: &str) -> Result<Vec<(Piece, usize)>, String> {
+    let s = fs::read_to_string(path).map_err(|err| format!("couldn't read {}: {}", path, err))?;
+    parse_owners(&s).map_err(|err| format!("{}: {}", path, err))
+}
+///liest die Scheiben und ihre Käse aus dem Text einer mit write_owners geschriebenen Datei
+fn parse_owners(s: &str) -> Result<Vec<(Piece, usize)>, String> {
+    let mut lines = s.lines();
+    let n_pieces: usize = lines
+        .next()
+        .ok_or_else(|| "empty owners file".to_string())?
+        .trim()
+        .parse()
+        .map_err(|_| "couldn't extract number of pieces".to_string())?;
+    let owners = lines
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let values = line
+                .split_whitespace()
+                .map(|value| value.parse::<u32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| format!("couldn't parse line: {:?}", line))?;
+            match values[..] {
+                [width, height, owner] => Ok((Piece::new(width, height), owner as usize)),
+                _ => Err(format!("couldn't parse line: {:?}", line)),
+            }
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if n_pieces != owners.len() {
+        return Err(format!(
+            "expected {} pieces, found {}",
+            n_pieces,
+            owners.len()
+        ));
+    }
+    Ok(owners)
+}
@@ -0,0 +1,19 @@
+//! Setzt Käsequader aus Scheiben wieder zusammen
+//! Die Module werden vom Kommandozeilenprogramm verwendet,
+//! können aber auch von anderen Programmen und Tests genutzt werden
+pub mod cheese;
+pub mod cheese_builder;
+pub mod eater;
+pub mod experiment;
+pub mod generator;
+pub mod mesh;
+pub mod minimize;
+pub mod pieces_map;
+pub mod prev_pieces;
+pub mod reference;
+pub mod report;
+pub mod score;
+pub mod solution;
+pub mod svg;
+pub mod verify;
+pub mod volume;
@@ -1,90 +1,276 @@
-mod cheese;
-mod cheese_builder;
-mod pieces_map;
-mod prev_pieces;
-
-use cheese::Piece;
-use clap::{arg, Parser};
-use pieces_map::PiecesMap;
-use rand::{prelude::*, rngs::ThreadRng, seq::SliceRandom, thread_rng};
-use std::{
-    fs::{self, File},
-    io::BufWriter,
-    io::Write,
-    time::Instant,
+use clap::{Args, Parser, Subcommand};
+use kaese::{
+    cheese::Piece,
+    cheese_builder::{
+        construct_cheeses, enumerate_cheeses, find_alternative, SearchStats, SolverConfig,
+    },
+    eater::EatModel,
+    experiment::{run_experiment, write_csv, ExperimentConfig},
+    generator::{add_random_sizes, generate_pile, read_owners, write_owners},
+    mesh::{write_obj, write_stl},
+    minimize::{minimize, Failure},
+    pieces_map::PiecesMap,
+    reference::{compare_with_reference, reference_decompositions, Differential},
+    report::{write_html, Report},
+    score::score,
+    solution::{
+        read_pieces, read_solution_pieces, run_length_encode, write_pieces, write_pile, Solution,
+        SolutionPiece,
+    },
+    svg::write_svg,
+    verify::{compare_pieces, verify_sequence},
+    volume::{candidate_sizes, pile_size},
 };
+use rand::{prelude::*, rngs::StdRng, seq::SliceRandom, thread_rng};
+use rustc_hash::FxHashMap;
+use std::{panic, path::Path, process, time::Instant};
 
-use crate::cheese_builder::construct_cheeses;
-
+// Gibt bei einem Fehler die Meldung aus und beendet das Programm mit Fehlercode
+fn exit_on_err<T>(result: Result<T, String>) -> T {
+    result.unwrap_or_else(|err| {
+        println!("Fehler: {}", err);
+        process::exit(1);
+    })
+}
 /// Lädt die Stücke aus einer Datei
 fn load_pieces(path: &str) -> Vec<Piece> {
-    let s = fs::read_to_string(path).expect("couldn't read file");
-    let mut lines = s.split("\r\n");
-    let n_pieces: usize = lines
-        .next()
-        .expect("empty data file")
-        .parse()
-        .expect("couldn't extract number of pieces");
-    let pieces: Vec<Piece> = lines
-        .filter_map(|line| -> Option<Piece> {
-            if line.is_empty() {
-                None
-            } else {
-                Some(
-                    line.split(' ')
-                        .collect::<Vec<&str>>()
-                        .try_into()
-                        .expect("couldn't parse line"),
-                )
-            }
-        })
-        .collect();
-    assert_eq!(n_pieces, pieces.len());
-    println!("\t{} Scheiben aus {} gelesen", n_pieces, path);
+    let pieces = exit_on_err(read_pieces(path));
+    println!("\t{} Scheiben aus {} gelesen", pieces.len(), path);
     pieces
 }
-/// Schreibt Käsescheiben in eine Datei
-fn write_pieces(path: &str, pieces: &Vec<Piece>) {
-    let file = File::create(path).expect("couldn't create file");
-    let mut writer = BufWriter::new(file);
-    writeln!(writer, "{}", pieces.len()).expect("couldn't write number of pieces");
-    for piece in pieces {
-        writeln!(writer, "{} {}", piece.0, piece.1).expect("couldn't write piece");
-    }
-    writer.flush().expect("couldn't flush writer");
-}
 
-/// Entfernt Scheiben mit einer bestimmten Wahrscheinlichkeit
-fn eat_pieces(pieces: Vec<Piece>, rng: &mut ThreadRng, prob: f64) -> Vec<Piece> {
-    let mut new_pieces = vec![];
-    let mut last_eaten = false;
-    let mut n_eaten = 0;
-    for (_i, piece) in pieces.into_iter().enumerate() {
-        if rng.gen_bool(1.0 - prob) || last_eaten {
-            new_pieces.push(piece);
-            last_eaten = false;
-        } else {
-            last_eaten = true;
-            n_eaten += 1;
-        }
-    }
-    println!("{} Scheiben wurden gegessen\n", n_eaten);
-    new_pieces
+// Liest eine Käsegröße im Format "a,b,c" ein
+fn parse_size(s: &str) -> Result<[u32; 3], String> {
+    let size = s
+        .split(',')
+        .map(|len| len.trim().parse::<u32>().map_err(|err| err.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    size.try_into()
+        .map_err(|_| "size needs three side lengths, e.g. 10,8,6".to_string())
 }
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Opts {
+    #[command(subcommand)]
+    command: Option<Command>,
     #[arg(long)]
     ///Wahrscheinlichkeit mit der Scheiben entfernt werden
     eat_prob: Option<f64>,
-    ///Wird automatisch gesetzt wenn --eat-prob gesetzt ist
+    ///Wie Scheiben entfernt werden: independent:p, runs:p:k, face:p, exactly:n, start:n oder end:n
+    ///die Modelle beziehen sich auf die Reihenfolge der Scheiben in den Dateien
+    #[arg(long, conflicts_with = "eat_prob")]
+    eat_model: Option<EatModel>,
+    ///Speichert die Scheiben in der Reihenfolge der Dateien (mit --eat-prob gemischt),
+    ///gegessene Scheiben sind markiert
+    #[arg(long)]
+    eaten: Option<String>,
+    ///Wird automatisch gesetzt wenn --eat-prob oder --eat-model gesetzt ist
     #[arg(long, default_value = "false")]
     find_missing: bool,
+    ///Wie viele fehlende Scheiben direkt hintereinander vermutet werden dürfen
+    #[arg(long, default_value = "1")]
+    max_missing_run: usize,
+    ///Wie viele Scheiben am Anfang der Schnittreihenfolge (im Inneren des Käses) fehlen dürfen
+    #[arg(long, default_value = "0")]
+    max_missing_start: usize,
+    ///Sucht auch dann nach fehlenden Scheiben, wenn echte Scheiben passen (langsamer)
+    #[arg(long, default_value = "false")]
+    eager_missing: bool,
+    ///Wie viele fehlende Scheiben ein Käse höchstens haben darf
+    #[arg(long)]
+    max_missing: Option<u32>,
+    ///Wie viele fehlende Scheiben alle Käse zusammen höchstens haben dürfen
+    #[arg(long)]
+    max_missing_total: Option<u32>,
+    ///Führt Pfade zusammen, die auf verschiedenen Wegen denselben Käse mit denselben
+    ///übrigen Scheiben erreichen (spart Zeit und Speicher bei vielen gleichen Scheiben)
+    #[arg(long, default_value = "false", conflicts_with = "check_unique")]
+    transpositions: bool,
+    ///Die bekannte Größe des ursprünglichen Käses, z.B. 10,8,6, nur Käse dieser Größe werden gefunden,
+    ///mit --find-missing werden fehlende äußere Scheiben bis zu dieser Größe ergänzt
+    #[arg(long, value_parser = parse_size)]
+    target: Option<[u32; 3]>,
+    ///Das bekannte Volumen des ursprünglichen Käses, nur Käse mit diesem Volumen werden gefunden
+    ///(die möglichen Größen zeigt der Befehl candidates)
+    #[arg(long)]
+    target_volume: Option<u64>,
+    ///Vergleicht jeden gefundenen Käse mit den anderen Pfaden, die im selben Schritt der Suche
+    ///enden, gibt es dort keine andere, werden alle Reihenfolgen der Scheiben aufgezählt,
+    ///eine andere Lösung wird in <name>_alternative.txt gespeichert
+    #[arg(long, default_value = "false")]
+    check_unique: bool,
+    ///Zerlegt die Scheiben mit Backtracking genau in möglichst wenige Käse, sodass alle Scheiben
+    ///verwendet werden, anstatt Käse nacheinander mit einer Mindestlänge zu suchen (langsamer),
+    ///mit --find-missing mit möglichst wenigen hinzugefügten Scheiben
+    #[arg(long, default_value = "false", conflicts_with = "check_unique")]
+    exact: bool,
+    ///In wie viele Käse die Scheiben höchstens zerlegt werden dürfen (nur mit --exact)
+    #[arg(long, requires = "exact")]
+    max_cheeses: Option<usize>,
+    ///Alle Scheiben stammen von einem Käse, Pfade, die wegen Volumen und Seitenlängen keinen
+    ///Käse aus allen Scheiben mehr ergeben können, werden verworfen (nur ohne --find-missing)
+    #[arg(long, default_value = "false")]
+    single_cheese: bool,
+    ///Gibt alle Scheibenreihenfolgen aus, die alle Scheiben zu einem Käse zusammensetzen,
+    ///anstatt nur die erste zu speichern
+    #[arg(long, default_value = "false")]
+    enumerate: bool,
+    ///Hört nach so vielen Lösungen auf (nur mit --enumerate)
+    #[arg(long, requires = "enumerate")]
+    limit: Option<usize>,
+    ///Schreibt die Lösung im kompakten Format (gleiche aufeinanderfolgende Scheiben als "anzahl × w h")
+    #[arg(long, default_value = "false")]
+    compact: bool,
+    ///Speichert zusätzlich eine isometrische Darstellung jedes Käses als SVG
+    #[arg(long, default_value = "false")]
+    svg: bool,
+    ///Speichert die Scheiben jedes Käses zusätzlich als 3D-Modell im OBJ-Format
+    #[arg(long, default_value = "false")]
+    obj: bool,
+    ///Speichert die Scheiben jedes Käses zusätzlich als 3D-Modell im ASCII-STL-Format
+    #[arg(long, default_value = "false")]
+    stl: bool,
+    ///Speichert einen Bericht über den Durchlauf als eigenständige HTML-Datei
+    #[arg(long)]
+    report: Option<String>,
     ///Die Dateien, aus denen die Scheiben geladen werden sollen
     #[arg(required = true)]
     files: Vec<String>,
 }
-// Lädt die Scheiben aus einer Datei, mischt sie und entfernt ggf. Stücke
+#[derive(Subcommand, Debug)]
+enum Command {
+    ///Überprüft, ob eine Lösung aus den Scheiben einer Datei einen Käse ergibt
+    Verify {
+        ///Die Datei mit den Scheiben
+        slices: String,
+        ///Die Datei mit der Scheibenreihenfolge
+        solution: String,
+    },
+    ///Erzeugt zufällig aufgeschnittene Käse, deren Scheiben gemischt werden
+    Generate(GenerateOpts),
+    ///Löst viele erzeugte Käse für verschiedene Größen und eat_prob und speichert die Ergebnisse als CSV
+    Experiment {
+        ///Die Größe der Käse, z.B. 10,8,6, kann mehrfach angegeben werden
+        #[arg(long, value_parser = parse_size, required = true)]
+        size: Vec<[u32; 3]>,
+        ///Die Wahrscheinlichkeiten, mit der Scheiben gegessen werden, z.B. 0,0.05,0.1
+        #[arg(long, value_delimiter = ',', default_value = "0")]
+        eat_prob: Vec<f64>,
+        ///Wie viele Käse pro Kombination gelöst werden
+        #[arg(long, default_value = "10")]
+        runs: usize,
+        ///Startwert für die Zufallsgeneratoren
+        #[arg(long, default_value = "0")]
+        seed: u64,
+        ///Die CSV-Datei, in die die Ergebnisse geschrieben werden
+        #[arg(long, short, default_value = "experiment.csv")]
+        output: String,
+    },
+    ///Listet die möglichen Größen eines Käses aus allen Scheiben auf,
+    ///passend zur gesamten Fläche und den größten Scheiben
+    Candidates {
+        ///Die Dateien, aus denen die Scheiben geladen werden sollen
+        #[arg(required = true)]
+        files: Vec<String>,
+        ///Das Volumen des Käses, sonst die gesamte Fläche der Scheiben
+        #[arg(long)]
+        target_volume: Option<u64>,
+        ///Ob Scheiben fehlen können, dann sind weniger Größen ausgeschlossen
+        #[arg(long, default_value = "false")]
+        find_missing: bool,
+    },
+    ///Zählt mit dem langsamen Referenz-Löser alle Zerlegungen kleiner Stapel auf
+    ///und vergleicht sie mit dem Ergebnis der Suche
+    Reference {
+        ///Die Dateien, aus denen die Scheiben geladen werden sollen
+        #[arg(required = true)]
+        files: Vec<String>,
+        ///Die maximale Anzahl an Käsen in einer Zerlegung
+        #[arg(long, default_value = "1")]
+        max_cheeses: usize,
+        ///Bricht nach so vielen Zerlegungen ab
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    ///Erzeugt viele kleine Käse und vergleicht die Suche jeweils mit dem Referenz-Löser,
+    ///Stapel, bei denen sie sich unterscheiden, werden als differential_<i>.txt gespeichert
+    Differential {
+        ///Die Größe der Käse, z.B. 4,3,3, kann mehrfach angegeben werden
+        #[arg(long, value_parser = parse_size, required = true)]
+        size: Vec<[u32; 3]>,
+        ///Wie viele Käse pro Größe erzeugt werden
+        #[arg(long, default_value = "100")]
+        runs: usize,
+        ///Startwert für die Zufallsgeneratoren
+        #[arg(long, default_value = "0")]
+        seed: u64,
+    },
+    ///Entfernt so lange Scheiben, wie ein Fehler der Suche bestehen bleibt,
+    ///und speichert den kleinsten gefundenen Stapel
+    Minimize {
+        ///Die Dateien, aus denen die Scheiben geladen werden sollen
+        #[arg(required_unless_present = "owners", conflicts_with = "owners")]
+        files: Vec<String>,
+        ///Lädt die Scheiben aus einer Zugehörigkeitsdatei von generate,
+        ///sodass zuerst ganze Käse entfernt werden
+        #[arg(long)]
+        owners: Option<String>,
+        ///Welcher Fehler bestehen bleiben soll: panic, none oder wrong[:max_cheeses]
+        ///(wrong vergleicht mit dem Referenz-Löser und ist nur für kleine Stapel geeignet)
+        #[arg(long, default_value = "panic")]
+        failure: Failure,
+        ///Sucht mit fehlenden Scheiben
+        #[arg(long, default_value = "false")]
+        find_missing: bool,
+        ///Die Datei, in die der kleinste Stapel geschrieben wird
+        #[arg(long, short, default_value = "minimized.txt")]
+        output: String,
+    },
+    ///Vergleicht die von der Suche hinzugefügten Scheiben mit den wirklich gegessenen Scheiben
+    Score {
+        ///Die richtige Lösung, z.B. von generate oder --eaten
+        truth: String,
+        ///Die von der Suche gefundene Lösung
+        found: String,
+    },
+}
+#[derive(Args, Debug)]
+struct GenerateOpts {
+    ///Die Größe eines Käses, z.B. 10,8,6, kann mehrfach angegeben werden
+    #[arg(long, value_parser = parse_size)]
+    size: Vec<[u32; 3]>,
+    ///Anzahl zusätzlicher Käse mit zufälliger Größe
+    #[arg(long, default_value = "0")]
+    cheeses: usize,
+    ///Die maximale Seitenlänge der zufälligen Käse
+    #[arg(long, default_value = "20")]
+    max_len: u32,
+    ///Zufällige Käse haben eine Seite, die genauso groß ist wie die eines anderen Käses
+    #[arg(long, default_value = "false")]
+    share_faces: bool,
+    ///Die Anzahl der Scheiben pro Käse, wird sonst durch die zufälligen Schnitte bestimmt
+    #[arg(long)]
+    slices: Option<usize>,
+    ///Startwert für den Zufallsgenerator
+    #[arg(long)]
+    seed: Option<u64>,
+    ///Isst von jedem Käse Scheiben (siehe --eat-model beim Suchen),
+    ///sie sind in den Lösungen markiert und fehlen in den Scheiben
+    #[arg(long)]
+    eat_model: Option<EatModel>,
+    ///Die Datei, in die die gemischten Scheiben geschrieben werden,
+    ///die richtige Reihenfolge wird in <name>_solution.txt (bzw. <name>_solution_<i>.txt)
+    ///und die Zugehörigkeit der Scheiben in <name>_owners.txt geschrieben
+    #[arg(long, short, default_value = "kaese.txt")]
+    output: String,
+}
+// Gibt das Modell zurück, mit dem Scheiben gegessen werden sollen
+fn eat_model(opts: &Opts) -> Option<EatModel> {
+    opts.eat_model
+        .or(opts.eat_prob.map(|prob| EatModel::Independent { prob }))
+}
+// Lädt die Scheiben aus einer Datei, entfernt ggf. Stücke und mischt sie
 fn prepare_pieces(opts: &Opts) -> Vec<Piece> {
     println!("Lade Scheiben...");
     let mut pieces = opts
@@ -94,18 +280,417 @@ fn prepare_pieces(opts: &Opts) -> Vec<Piece> {
         .collect::<Vec<_>>();
     println!();
     let mut rng = thread_rng();
-    println!("Mische Scheiben...\n");
-    pieces.shuffle(&mut rng);
-    if let Some(prob) = opts.eat_prob {
-        pieces = eat_pieces(pieces, &mut rng, prob);
+    // Mit --eat-prob wird wie bisher erst gemischt und dann gegessen,
+    // die anderen Modelle beziehen sich auf die Reihenfolge in den Dateien
+    let eat_after_shuffle = opts.eat_prob.is_some();
+    if eat_after_shuffle {
+        println!("Mische Scheiben...\n");
+        pieces.shuffle(&mut rng);
+    }
+    if let Some(model) = eat_model(opts) {
+        let Some(result) = model.eat(&pieces, &mut rng) else {
+            println!("{} braucht Scheiben in der Reihenfolge einer Lösung", model);
+            process::exit(1);
+        };
+        println!("{} Scheiben wurden gegessen", result.eaten.len());
+        if let Some(eaten_path) = &opts.eaten {
+            write_pieces(eaten_path, &result.marked_order());
+            println!("\tgegessene Scheiben in {} gespeichert", eaten_path);
+        }
+        println!();
+        pieces = result.pieces;
+    }
+    if !eat_after_shuffle {
+        println!("Mische Scheiben...\n");
+        pieces.shuffle(&mut rng);
     }
     pieces
 }
+// Überprüft eine Lösung, gibt den ersten Fehler aus und beendet das Programm mit Fehlercode
+fn verify(slices_path: &str, solution_path: &str) {
+    let pile = exit_on_err(read_pieces(slices_path));
+    let pieces = exit_on_err(read_solution_pieces(solution_path));
+    println!(
+        "{} Scheiben, Lösung mit {} Scheiben\n",
+        pile.len(),
+        pieces.len()
+    );
+    // Der Käse wird Scheibe für Scheibe nachgebaut, beginnend mit der ersten Scheibe (Dicke 1)
+    let sequence = pieces.iter().map(|piece| piece.piece).collect::<Vec<_>>();
+    let cheese = match verify_sequence(&sequence, None) {
+        Ok(cheese) => cheese,
+        Err(err) => {
+            println!("Fehler: {}", err);
+            process::exit(1);
+        }
+    };
+    // Es wird überprüft, ob genau die Scheiben aus der Datei als echte Scheiben verwendet wurden
+    let real = pieces
+        .iter()
+        .filter(|piece| !piece.is_added)
+        .map(|piece| piece.piece)
+        .collect::<Vec<_>>();
+    let wrong = compare_pieces(&real, &pile);
+    if !wrong.is_empty() {
+        println!("Fehler: die echten Scheiben der Lösung stimmen nicht mit den Scheiben überein");
+        for (piece, n) in wrong {
+            if n > 0 {
+                println!(
+                    "\t{}x{} ist {} mal zu oft in der Lösung",
+                    piece.0, piece.1, n
+                );
+            } else {
+                println!("\t{}x{} fehlt {} mal in der Lösung", piece.0, piece.1, -n);
+            }
+        }
+        process::exit(1);
+    }
+    println!("Lösung ist gültig");
+    println!("\tKäse: {:?}", cheese.size);
+    println!(
+        "\t{} Scheiben wurden hinzugefügt",
+        pieces.iter().filter(|piece| piece.is_added).count()
+    );
+}
+// Gibt eine Scheibe einer Lösung als "w×h" aus, "-" wenn die Lösung schon zu Ende ist
+fn format_piece(piece: Option<&SolutionPiece>) -> String {
+    piece.map_or("-".to_string(), |piece| {
+        format!("{}×{}", piece.piece.0, piece.piece.1)
+    })
+}
+// Gibt den Pfad einer Datei zurück, die zu einer Scheibendatei gehört,
+// z.B. kaese_solution.txt zu kaese.txt
+fn related_path(path: &str, suffix: &str) -> String {
+    let path = Path::new(path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}_{}.txt", stem, suffix))
+        .to_string_lossy()
+        .into_owned()
+}
+// Gibt einen Zufallsgenerator zurück, mit festem Startwert falls angegeben
+fn make_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+// Erzeugt zufällig aufgeschnittene Käse und speichert die gemischten Scheiben und die Lösungen
+fn generate(opts: &GenerateOpts) {
+    let mut rng = make_rng(opts.seed);
+    let mut sizes = opts.size.clone();
+    add_random_sizes(
+        &mut sizes,
+        opts.cheeses,
+        opts.max_len,
+        opts.share_faces,
+        &mut rng,
+    );
+    if sizes.is_empty() {
+        println!("Es muss mindestens ein Käse mit --size oder --cheeses angegeben werden");
+        process::exit(1);
+    }
+    let pile = exit_on_err(generate_pile(
+        &sizes,
+        opts.slices,
+        opts.eat_model.as_ref(),
+        &mut rng,
+    ));
+    write_pile(&opts.output, &pile.pieces);
+    println!(
+        "{} Käse in {} Scheiben geschnitten, Scheiben in {} gespeichert",
+        sizes.len(),
+        pile.pieces.len(),
+        opts.output
+    );
+    for (i, solution) in pile.solutions.iter().enumerate() {
+        let solution_path = if sizes.len() > 1 {
+            related_path(&opts.output, &format!("solution_{}", i))
+        } else {
+            related_path(&opts.output, "solution")
+        };
+        solution.write(&solution_path, false);
+        println!(
+            "\tKäse {:?} ({} Scheiben): Scheibenreihenfolge in {} gespeichert",
+            solution.cheese.size,
+            solution.pieces.len(),
+            solution_path
+        );
+    }
+    let owners_path = related_path(&opts.output, "owners");
+    write_owners(&owners_path, &pile);
+    println!(
+        "\tZugehörigkeit der Scheiben in {} gespeichert",
+        owners_path
+    );
+}
+// Vergleicht eine gefundene Lösung mit der richtigen Lösung
+fn print_score(truth_path: &str, found_path: &str) {
+    let truth = exit_on_err(Solution::load(truth_path));
+    let found = exit_on_err(Solution::load(found_path));
+    let score = score(&truth, &found);
+    println!(
+        "Käse: {:?}, gefunden: {:?} ({})",
+        score.truth_size,
+        score.found_size,
+        if score.size_matches() {
+            "richtig"
+        } else {
+            "falsch"
+        }
+    );
+    println!(
+        "\t{} Scheiben gegessen, {} hinzugefügt, davon {} richtig",
+        score.n_eaten, score.n_added, score.n_matched
+    );
+    println!("\tPrecision: {:.3}", score.precision());
+    println!("\tRecall: {:.3}", score.recall());
+    println!(
+        "\tAbweichung der Positionen: durchschnittlich {:.2}, maximal {}",
+        score.mean_position_error(),
+        score.position_errors.iter().max().unwrap_or(&0)
+    );
+}
+// Führt ein Experiment durch und speichert die Ergebnisse
+fn experiment(config: ExperimentConfig, output: &str) {
+    println!(
+        "Experiment mit {} Kombinationen, je {} Käse",
+        config.sizes.len() * config.eat_probs.len(),
+        config.runs
+    );
+    // Abstürze der Suche werden als Fehlschlag gezählt,
+    // die Meldungen würden die Ausgabe nur unübersichtlich machen
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let rows = run_experiment(&config, |row| {
+        println!(
+            "\tKäse {:?}, eat_prob {}: {}/{} richtig, {} abgestürzt, durchschnittlich {:?}",
+            row.size, row.eat_prob, row.n_success, row.runs, row.n_panics, row.mean_runtime
+        );
+    });
+    panic::set_hook(default_hook);
+    let rows = exit_on_err(rows);
+    write_csv(output, &rows);
+    println!("Ergebnisse in {} gespeichert", output);
+}
+// Gibt die möglichen Größen eines Käses aus allen Scheiben aus
+fn candidates(files: &[String], target_volume: Option<u64>, find_missing: bool) {
+    let pieces = files
+        .iter()
+        .flat_map(|path| load_pieces(path))
+        .collect::<Vec<_>>();
+    let pieces_map = PiecesMap::new(&pieces);
+    let (_, area) = pile_size(&pieces_map);
+    let sizes = candidate_sizes(&pieces_map, target_volume, find_missing);
+    println!(
+        "\n{} mögliche Größen mit Volumen {} (Fläche der Scheiben: {})",
+        sizes.len(),
+        target_volume.unwrap_or(area),
+        area
+    );
+    for size in sizes {
+        println!("\t{:?}", size);
+    }
+}
+// Zählt alle Zerlegungen der Scheiben auf und vergleicht sie mit der Suche
+fn reference(files: &[String], max_cheeses: usize, limit: Option<usize>) {
+    let pieces = files
+        .iter()
+        .flat_map(|path| load_pieces(path))
+        .collect::<Vec<_>>();
+    let decompositions = reference_decompositions(&pieces, max_cheeses, limit);
+    println!(
+        "\n{} Zerlegungen in höchstens {} Käse gefunden",
+        decompositions.len(),
+        max_cheeses
+    );
+    for (i, cheeses) in decompositions.iter().enumerate() {
+        println!("\tZerlegung {}:", i);
+        for solution in cheeses {
+            let order = solution
+                .pieces
+                .iter()
+                .map(|piece| format!("{}x{}", piece.piece.0, piece.piece.1))
+                .collect::<Vec<_>>();
+            println!("\t\t{:?}: {}", solution.cheese.size, order.join(" "));
+        }
+    }
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let differential = compare_with_reference(&pieces, max_cheeses, &SolverConfig::default());
+    panic::set_hook(default_hook);
+    println!("\nVergleich mit der Suche: {:?}", differential);
+}
+// Vergleicht die Suche auf vielen kleinen erzeugten Käsen mit dem Referenz-Löser
+fn differential(sizes: &[[u32; 3]], runs: usize, seed: u64) {
+    // Abstürze der Suche werden als Unterschied gezählt
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut n_failed = 0;
+    for size in sizes {
+        let mut counts: FxHashMap<Differential, usize> = FxHashMap::default();
+        for run in 0..runs {
+            let mut rng = StdRng::seed_from_u64(seed.wrapping_add(run as u64));
+            let pile = match generate_pile(&[*size], None, None, &mut rng) {
+                Ok(pile) => pile,
+                Err(err) => {
+                    panic::set_hook(default_hook);
+                    println!("Fehler: {}", err);
+                    process::exit(1);
+                }
+            };
+            let result = compare_with_reference(&pile.pieces, 1, &SolverConfig::default());
+            if result != Differential::Agree {
+                let path = format!("differential_{}.txt", n_failed);
+                write_pile(&path, &pile.pieces);
+                n_failed += 1;
+            }
+            *counts.entry(result).or_insert(0) += 1;
+        }
+        let mut counts = counts.into_iter().collect::<Vec<_>>();
+        counts.sort_unstable_by_key(|(result, _)| format!("{:?}", result));
+        println!("Käse {:?}: {:?}", size, counts);
+    }
+    panic::set_hook(default_hook);
+    if n_failed > 0 {
+        println!(
+            "{} Stapel mit Unterschieden als differential_<i>.txt gespeichert",
+            n_failed
+        );
+        process::exit(1);
+    }
+    println!("Keine Unterschiede gefunden");
+}
+// Verkleinert einen Stapel, bei dem die Suche fehlschlägt
+fn minimize_pile(
+    files: &[String],
+    owners_path: Option<&str>,
+    failure: Failure,
+    find_missing: bool,
+    output: &str,
+) {
+    let (pieces, owners) = match owners_path {
+        Some(path) => {
+            let (pieces, owners): (Vec<_>, Vec<_>) =
+                exit_on_err(read_owners(path)).into_iter().unzip();
+            println!("\t{} Scheiben aus {} gelesen", pieces.len(), path);
+            (pieces, Some(owners))
+        }
+        None => (
+            files.iter().flat_map(|path| load_pieces(path)).collect(),
+            None,
+        ),
+    };
+    println!(
+        "\nVerkleinere Stapel, solange der Fehler {} auftritt...",
+        failure
+    );
+    // Die Suche wird sehr oft ausgeführt, die Meldungen der Abstürze werden abgeschaltet
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let minimized = minimize(
+        &pieces,
+        owners.as_deref(),
+        |pieces| failure.occurs(pieces, find_missing),
+        |n_left| println!("\t{} Scheiben übrig", n_left),
+    );
+    panic::set_hook(default_hook);
+    let Some(minimized) = minimized else {
+        println!("Der Fehler {} tritt bei diesen Scheiben nicht auf", failure);
+        process::exit(1);
+    };
+    write_pile(output, &minimized);
+    println!(
+        "{} von {} Scheiben übrig, in {} gespeichert",
+        minimized.len(),
+        pieces.len(),
+        output
+    );
+}
 // Die Hauptfunktion
 fn main() {
-    let mut opts = Opts::parse();
+    let opts = Opts::parse();
+    match &opts.command {
+        Some(Command::Verify { slices, solution }) => verify(slices, solution),
+        Some(Command::Generate(generate_opts)) => generate(generate_opts),
+        Some(Command::Score { truth, found }) => print_score(truth, found),
+        Some(Command::Reference {
+            files,
+            max_cheeses,
+            limit,
+        }) => reference(files, *max_cheeses, *limit),
+        Some(Command::Candidates {
+            files,
+            target_volume,
+            find_missing,
+        }) => candidates(files, *target_volume, *find_missing),
+        Some(Command::Differential { size, runs, seed }) => differential(size, *runs, *seed),
+        Some(Command::Minimize {
+            files,
+            owners,
+            failure,
+            find_missing,
+            output,
+        }) => minimize_pile(files, owners.as_deref(), *failure, *find_missing, output),
+        Some(Command::Experiment {
+            size,
+            eat_prob,
+            runs,
+            seed,
+            output,
+        }) => experiment(
+            ExperimentConfig {
+                sizes: size.clone(),
+                eat_probs: eat_prob.clone(),
+                runs: *runs,
+                seed: *seed,
+            },
+            output,
+        ),
+        None => solve(opts),
+    }
+}
+// Gibt alle Scheibenreihenfolgen aus, die alle Scheiben zu einem Käse zusammensetzen
+fn enumerate(
+    pieces_map: Box<PiecesMap>,
+    n_pieces: usize,
+    config: &SolverConfig,
+    limit: Option<usize>,
+) {
+    let start = Instant::now();
+    let mut n_solutions = 0;
+    for solution in
+        enumerate_cheeses(pieces_map, n_pieces, config).take(limit.unwrap_or(usize::MAX))
+    {
+        // Die Reihenfolge wird kompakt ausgegeben, gleiche Scheiben hintereinander zusammengefasst
+        let order = run_length_encode(&solution.pieces)
+            .into_iter()
+            .map(|(count, piece)| {
+                let marker = if piece.is_added { " *" } else { "" };
+                if count > 1 {
+                    format!("{} × {} {}{}", count, piece.piece.0, piece.piece.1, marker)
+                } else {
+                    format!("{} {}{}", piece.piece.0, piece.piece.1, marker)
+                }
+            })
+            .collect::<Vec<_>>();
+        println!(
+            "{}: {:?}: {}",
+            n_solutions,
+            solution.cheese.size,
+            order.join(", ")
+        );
+        n_solutions += 1;
+    }
+    println!(
+        "\n{} Lösungen in {:?} gefunden",
+        n_solutions,
+        start.elapsed()
+    );
+}
+// Sucht Käse in den Scheiben aus den Dateien
+fn solve(mut opts: Opts) {
     //println!("{:#?}", opts);
-    if opts.eat_prob.is_some() {
+    if eat_model(&opts).is_some() {
         opts.find_missing = true;
     }
     // Scheiben werden vorbereitet
@@ -113,19 +698,70 @@ fn main() {
     // Die Scheiben werden in eine HashMap geladen,
     // die die Anzahl der Scheiben mit einer bestimmten Größe speichert
     let pieces_map = Box::new(PiecesMap::new(&pieces));
+    // Informationen über die Scheiben werden ausgegeben
+    let pieces_info = pieces_map.info();
+    println!("Informationen über die Käsescheiben:");
+    println!(
+        "\tMaximale Anzahl eines einzelnen Stücks: {}\n\tMehrfache Scheiben: {}\n\tAnzahl verschiener Scheiben: {}",
+        pieces_info.max_n,
+        pieces_info.n_multiple,
+        pieces_info.n_different
+    );
+    println!();
+    let config = SolverConfig {
+        find_missing: opts.find_missing,
+        max_missing_run: opts.max_missing_run,
+        max_missing_start: opts.max_missing_start,
+        eager_missing: opts.eager_missing,
+        max_missing: opts.max_missing,
+        max_missing_total: opts.max_missing_total,
+        transpositions: opts.transpositions,
+        target: opts.target,
+        target_volume: opts.target_volume,
+        detect_ambiguity: opts.check_unique,
+        exact: opts.exact,
+        max_cheeses: opts.max_cheeses,
+        single_cheese: opts.single_cheese,
+    };
+    if opts.enumerate {
+        enumerate(pieces_map, pieces.len(), &config, opts.limit);
+        return;
+    }
     // Timer wird gestartet
     let start = Instant::now();
     // Es wird versucht Käse zu finden
-    let result = construct_cheeses(pieces_map, pieces.len(), opts.find_missing);
+    let mut stats = SearchStats::default();
+    let result = construct_cheeses(pieces_map, pieces.len(), &config, &mut stats);
     // Die Zeit wird gemessen
     let elapsed = start.elapsed();
     // Die Ergebnisse werden ausgegeben
-    if result.is_empty() {
+    if result.is_empty() && stats.n_over_budget > 0 {
+        println!(
+            "Kein Käse mit höchstens {} fehlenden Scheiben gefunden ({} Pfade verworfen)",
+            opts.max_missing
+                .into_iter()
+                .chain(opts.max_missing_total)
+                .min()
+                .unwrap(),
+            stats.n_over_budget
+        );
+    } else if result.is_empty() && stats.n_infeasible + stats.starts.n_infeasible > 0 {
+        println!(
+            "Kein Käse mit einer der möglichen Größen gefunden ({} Pfade und {} Startscheiben verworfen)",
+            stats.n_infeasible, stats.starts.n_infeasible
+        );
+    } else if result.is_empty() && opts.exact {
+        println!(
+            "Die Scheiben lassen sich nicht in höchstens {} Käse zerlegen",
+            opts.max_cheeses.unwrap_or(pieces.len())
+        );
+    } else if result.is_empty() {
         println!("Kein Käse gefunden");
     } else {
         println!("{} Käse gefunden: ", result.len());
     }
     let n_results = result.len();
+    let mut solutions = vec![];
     for (i, (cheese, path)) in result.iter().enumerate() {
         if n_results > 1 {
             println!("   {}:", i);
@@ -134,19 +770,170 @@ fn main() {
         if opts.find_missing {
             println!("\t{} Scheiben wurden hinzugefügt", path.n_added);
         }
-        let path = path.curr.get_pieces().into_iter().rev().collect::<Vec<_>>();
-        println!("\tStartscheibe: {:?}", path[0]);
-        println!("\tLetzte Scheibe: {:?}", path.last().unwrap());
-        let file_path = if n_results > 1 {
-            format!("solution_{}.txt", i)
+        let solution = Solution::from_path(*cheese, path);
+        println!("\tStartscheibe: {:?}", solution.pieces[0].piece);
+        println!(
+            "\tLetzte Scheibe: {:?}",
+            solution.pieces.last().unwrap().piece
+        );
+        let file_name = if n_results > 1 {
+            format!("solution_{}", i)
         } else {
-            "solution.txt".to_string()
+            "solution".to_string()
         };
+        let file_path = format!("{}.txt", file_name);
         // Die Scheibenreihenfolge wird in eine Datei geschrieben
-        write_pieces(&file_path, &path);
+        solution.write(&file_path, opts.compact);
         println!("\tScheibenreihenfolge in {} gespeichert", file_path);
+        if opts.check_unique {
+            // Ein anderer Pfad, der im selben Schritt geendet hat, ist schon eine andere Lösung,
+            // sonst werden alle Reihenfolgen der Scheiben durchsucht
+            let same_step = stats
+                .ambiguities
+                .iter()
+                .find(|ambiguity| ambiguity.cheese_i == i)
+                .cloned();
+            let found_in_step = same_step.is_some();
+            match same_step.or_else(|| find_alternative(i, &solution, &config)) {
+                Some(ambiguity) => {
+                    let alternative_path = format!("{}_alternative.txt", file_name);
+                    ambiguity.alternative.write(&alternative_path, opts.compact);
+                    let diverges = format!(
+                        "ab Scheibe {} ({} statt {})",
+                        ambiguity.diverges_at + 1,
+                        format_piece(ambiguity.alternative.pieces.get(ambiguity.diverges_at)),
+                        format_piece(solution.pieces.get(ambiguity.diverges_at))
+                    );
+                    if found_in_step {
+                        println!(
+                            "\tLösung ist nicht eindeutig: {} Lösungen haben im selben Schritt geendet, die erste andere weicht {} ab",
+                            ambiguity.n_solutions, diverges
+                        );
+                    } else {
+                        println!(
+                            "\tLösung ist nicht eindeutig: eine andere Reihenfolge weicht {} ab",
+                            diverges
+                        );
+                    }
+                    println!("\tAndere Lösung in {} gespeichert", alternative_path);
+                }
+                None => println!(
+                    "\tLösung ist eindeutig: keine andere Reihenfolge dieser Scheiben ergibt einen Käse"
+                ),
+            }
+        }
+        if opts.svg {
+            let svg_path = format!("{}.svg", file_name);
+            write_svg(&svg_path, &solution);
+            println!("\tDarstellung in {} gespeichert", svg_path);
+        }
+        if opts.obj {
+            let obj_path = format!("{}.obj", file_name);
+            write_obj(&obj_path, &solution);
+            println!("\t3D-Modell in {} gespeichert", obj_path);
+        }
+        if opts.stl {
+            let stl_path = format!("{}.stl", file_name);
+            write_stl(&stl_path, &solution);
+            println!("\t3D-Modell in {} gespeichert", stl_path);
+        }
         println!();
+        solutions.push(solution);
     }
 
     println!("Suche hat {:?} gedauert", elapsed);
+    println!(
+        "\t{} Pfade erzeugt, höchstens {} gleichzeitig verfolgt",
+        stats.n_paths, stats.peak_frontier
+    );
+    let starts = stats.starts;
+    println!(
+        "\t{} Startscheiben ausprobiert, {} verworfen ({} ohne passende zweite Scheibe, {} zu dick, {} ohne mögliche Größe)",
+        starts.n_tried,
+        starts.n_unextendable + starts.n_too_thick + starts.n_infeasible,
+        starts.n_unextendable,
+        starts.n_too_thick,
+        starts.n_infeasible
+    );
+    if stats.n_transpositions > 0 {
+        println!(
+            "\t{} Pfade mit gleichem Zustand zusammengeführt",
+            stats.n_transpositions
+        );
+    }
+    if stats.n_infeasible > 0 {
+        println!(
+            "\t{} Pfade verworfen, die keine mögliche Käsegröße mehr erreichen können",
+            stats.n_infeasible
+        );
+    }
+    if stats.n_backtracks > 0 {
+        println!(
+            "\t{} Käse wieder verworfen, weil sich der Rest nicht zerlegen ließ",
+            stats.n_backtracks
+        );
+    }
+    if stats.n_over_budget > 0 {
+        println!(
+            "\t{} Pfade wegen zu vieler fehlender Scheiben verworfen",
+            stats.n_over_budget
+        );
+    }
+    if let Some(report_path) = &opts.report {
+        let report = Report {
+            files: opts.files.clone(),
+            config: vec![
+                ("find_missing".to_string(), opts.find_missing.to_string()),
+                (
+                    "max_missing_run".to_string(),
+                    opts.max_missing_run.to_string(),
+                ),
+                (
+                    "max_missing_start".to_string(),
+                    opts.max_missing_start.to_string(),
+                ),
+                ("eager_missing".to_string(), opts.eager_missing.to_string()),
+                (
+                    "max_missing".to_string(),
+                    opts.max_missing.map_or("-".to_string(), |n| n.to_string()),
+                ),
+                (
+                    "transpositions".to_string(),
+                    opts.transpositions.to_string(),
+                ),
+                (
+                    "max_missing_total".to_string(),
+                    opts.max_missing_total
+                        .map_or("-".to_string(), |n| n.to_string()),
+                ),
+                (
+                    "target".to_string(),
+                    opts.target
+                        .map_or("-".to_string(), |size| format!("{:?}", size)),
+                ),
+                (
+                    "target_volume".to_string(),
+                    opts.target_volume
+                        .map_or("-".to_string(), |v| v.to_string()),
+                ),
+                ("check_unique".to_string(), opts.check_unique.to_string()),
+                ("exact".to_string(), opts.exact.to_string()),
+                ("single_cheese".to_string(), opts.single_cheese.to_string()),
+                (
+                    "max_cheeses".to_string(),
+                    opts.max_cheeses.map_or("-".to_string(), |n| n.to_string()),
+                ),
+                (
+                    "eat_model".to_string(),
+                    eat_model(&opts).map_or("-".to_string(), |model| model.to_string()),
+                ),
+            ],
+            pieces_info,
+            solutions,
+            elapsed,
+            stats,
+        };
+        write_html(report_path, &report);
+        println!("Bericht in {} gespeichert", report_path);
+    }
 }
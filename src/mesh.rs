@@ -0,0 +1,101 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use crate::solution::{PlacedPiece, Solution};
+
+//die Seiten eines Quaders als Indizes der Ecken (siehe box_corners),
+//gegen den Uhrzeigersinn von außen gesehen, zusammen mit der Normalen
+const BOX_FACES: [([usize; 4], [i8; 3]); 6] = [
+    ([0, 2, 3, 1], [0, 0, -1]),
+    ([4, 5, 7, 6], [0, 0, 1]),
+    ([0, 1, 5, 4], [0, -1, 0]),
+    ([2, 6, 7, 3], [0, 1, 0]),
+    ([0, 4, 6, 2], [-1, 0, 0]),
+    ([1, 3, 7, 5], [1, 0, 0]),
+];
+
+///gibt die acht Ecken einer Scheibe zurück,
+///Bit 0 des Index steht für x, Bit 1 für y und Bit 2 für z
+fn box_corners(piece: &PlacedPiece) -> [[u32; 3]; 8] {
+    std::array::from_fn(|i| {
+        std::array::from_fn(|axis| {
+            if i & (1 << axis) != 0 {
+                piece.origin[axis] + piece.size[axis]
+            } else {
+                piece.origin[axis]
+            }
+        })
+    })
+}
+///gibt den Namen einer Scheibe im Modell zurück
+fn piece_name(i: usize, piece: &PlacedPiece) -> String {
+    if piece.is_added {
+        //hypothetische Scheiben sind am Namen erkennbar
+        format!("piece_{}_added", i)
+    } else {
+        format!("piece_{}", i)
+    }
+}
+///schreibt die Scheiben als Wavefront OBJ, jede Scheibe ist ein eigenes Objekt
+pub fn write_obj_to(writer: &mut impl Write, solution: &Solution) -> std::io::Result<()> {
+    let (_, pieces) = solution.place_pieces();
+    writeln!(
+        writer,
+        "# Käse {:?} aus {} Scheiben",
+        solution.cheese.size,
+        pieces.len()
+    )?;
+    for (i, piece) in pieces.iter().enumerate() {
+        writeln!(writer, "o {}", piece_name(i, piece))?;
+        for [x, y, z] in box_corners(piece) {
+            writeln!(writer, "v {} {} {}", x, y, z)?;
+        }
+        //die Indizes der Ecken beginnen bei 1 und zählen über alle Objekte weiter
+        let first = i * 8 + 1;
+        for (face, _) in BOX_FACES {
+            let [a, b, c, d] = face.map(|corner| corner + first);
+            writeln!(writer, "f {} {} {} {}", a, b, c, d)?;
+        }
+    }
+    Ok(())
+}
+///schreibt die Scheiben als ASCII STL, jede Scheibe ist ein eigener Körper
+pub fn write_stl_to(writer: &mut impl Write, solution: &Solution) -> std::io::Result<()> {
+    let (_, pieces) = solution.place_pieces();
+    for (i, piece) in pieces.iter().enumerate() {
+        let name = piece_name(i, piece);
+        let corners = box_corners(piece);
+        writeln!(writer, "solid {}", name)?;
+        for (face, [nx, ny, nz]) in BOX_FACES {
+            //STL kennt nur Dreiecke, deshalb wird jede Seite in zwei Dreiecke geteilt
+            for triangle in [[face[0], face[1], face[2]], [face[0], face[2], face[3]]] {
+                writeln!(writer, "  facet normal {} {} {}", nx, ny, nz)?;
+                writeln!(writer, "    outer loop")?;
+                for corner in triangle {
+                    let [x, y, z] = corners[corner];
+                    writeln!(writer, "      vertex {} {} {}", x, y, z)?;
+                }
+                writeln!(writer, "    endloop")?;
+                writeln!(writer, "  endfacet")?;
+            }
+        }
+        writeln!(writer, "endsolid {}", name)?;
+    }
+    Ok(())
+}
+///schreibt die Scheiben als Wavefront OBJ in eine Datei
+pub fn write_obj(path: &str, solution: &Solution) {
+    let file = File::create(path).expect("couldn't create file");
+    let mut writer = BufWriter::new(file);
+    write_obj_to(&mut writer, solution).expect("couldn't write obj file");
+    writer.flush().expect("couldn't flush writer");
+}
+///schreibt die Scheiben als ASCII STL in eine Datei
+pub fn write_stl(path: &str, solution: &Solution) {
+    let file = File::create(path).expect("couldn't create file");
+    let mut writer = BufWriter::new(file);
+    write_stl_to(&mut writer, solution).expect("couldn't write stl file");
+    writer.flush().expect("couldn't flush writer");
+}
@@ -0,0 +1,148 @@
+//Verkleinert Stapel, bei denen die Suche fehlschlägt, damit der Fehler leichter zu finden ist
+use std::{
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    str::FromStr,
+};
+
+use crate::{
+    cheese::Piece,
+    cheese_builder::{construct_cheeses, SearchStats, SolverConfig},
+    pieces_map::PiecesMap,
+    reference::{compare_with_reference, Differential},
+};
+
+///wann ein Stapel als fehlerhaft gilt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    ///die Suche stürzt ab
+    Panic,
+    ///die Suche findet keinen Käse
+    NoCheese,
+    ///die Suche findet etwas anderes als der Referenz-Löser (nur für kleine Stapel)
+    Wrong { max_cheeses: usize },
+}
+
+impl Failure {
+    ///überprüft, ob der Fehler bei diesen Scheiben auftritt
+    ///die Meldungen der Abstürze sollten vorher abgeschaltet werden, da sehr oft gesucht wird
+    pub fn occurs(&self, pieces: &[Piece], find_missing: bool) -> bool {
+        if let Failure::Wrong { max_cheeses } = *self {
+            return !matches!(
+                compare_with_reference(pieces, max_cheeses, &SolverConfig::default()),
+                Differential::Agree | Differential::MainPanicked
+            );
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let pieces_map = Box::new(PiecesMap::new(&pieces.to_vec()));
+            construct_cheeses(
+                pieces_map,
+                pieces.len(),
+                &SolverConfig {
+                    find_missing,
+                    ..Default::default()
+                },
+                &mut SearchStats::default(),
+            )
+        }));
+        match (self, result) {
+            (Failure::Panic, result) => result.is_err(),
+            (Failure::NoCheese, Ok(result)) => result.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+//liest einen Fehler im Format "panic", "none" oder "wrong[:max_cheeses]"
+impl FromStr for Failure {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "panic" => Ok(Failure::Panic),
+            None if s == "none" => Ok(Failure::NoCheese),
+            None if s == "wrong" => Ok(Failure::Wrong { max_cheeses: 1 }),
+            Some(("wrong", n)) => Ok(Failure::Wrong {
+                max_cheeses: n.parse().map_err(|err| format!("{}", err))?,
+            }),
+            _ => Err(format!(
+                "unknown failure '{}', expected panic, none or wrong[:max_cheeses]",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Panic => write!(f, "panic"),
+            Failure::NoCheese => write!(f, "none"),
+            Failure::Wrong { max_cheeses } => write!(f, "wrong:{}", max_cheeses),
+        }
+    }
+}
+
+///entfernt so lange Scheiben, wie is_failing für den Rest noch true zurückgibt
+///falls owners angegeben ist (siehe generator::read_owners), werden zuerst ganze Käse entfernt,
+///danach immer kleinere zusammenhängende Blöcke bis hin zu einzelnen Scheiben,
+///sodass am Ende keine einzelne Scheibe mehr entfernt werden kann
+///progress wird mit der Anzahl der übrigen Scheiben aufgerufen, wenn etwas entfernt wurde
+///gibt None zurück, falls der Fehler schon beim ganzen Stapel nicht auftritt
+pub fn minimize(
+    pieces: &[Piece],
+    owners: Option<&[usize]>,
+    mut is_failing: impl FnMut(&[Piece]) -> bool,
+    mut progress: impl FnMut(usize),
+) -> Option<Vec<Piece>> {
+    if !is_failing(pieces) {
+        return None;
+    }
+    let mut current = match owners {
+        Some(owners) => pieces.iter().cloned().zip(owners.iter().cloned()).collect(),
+        None => pieces.iter().map(|piece| (*piece, 0)).collect::<Vec<_>>(),
+    };
+    let to_pieces = |owned: &[(Piece, usize)]| owned.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+    //ganze Käse entfernen
+    if let Some(owners) = owners {
+        let mut groups = owners.to_vec();
+        groups.sort_unstable();
+        groups.dedup();
+        for group in groups {
+            let candidate = current
+                .iter()
+                .filter(|(_, owner)| *owner != group)
+                .cloned()
+                .collect::<Vec<_>>();
+            if !candidate.is_empty() && is_failing(&to_pieces(&candidate)) {
+                current = candidate;
+                progress(current.len());
+            }
+        }
+    }
+    //immer kleinere Blöcke entfernen
+    let mut chunk = current.len() / 2;
+    while chunk > 0 {
+        let mut removed_any = false;
+        let mut i = 0;
+        while i < current.len() {
+            let end = (i + chunk).min(current.len());
+            let mut candidate = current[..i].to_vec();
+            candidate.extend_from_slice(&current[end..]);
+            if !candidate.is_empty() && is_failing(&to_pieces(&candidate)) {
+                current = candidate;
+                removed_any = true;
+                progress(current.len());
+            } else {
+                i = end;
+            }
+        }
+        //wenn nichts entfernt werden konnte, wird mit halb so großen Blöcken weitergemacht
+        //sonst höchstens halb so groß wie der Rest, aber mindestens eine Scheibe
+        if !removed_any {
+            chunk /= 2;
+        } else {
+            chunk = chunk.min(current.len() / 2).max(1);
+        }
+    }
+    Some(to_pieces(&current))
+}
@@ -18,41 +18,44 @@ pub struct PiecesMap {
     pub added: FxHashMap<Piece, u32>,
 }
 
+//Informationen über die Käsescheiben, z.B. für die Ausgabe
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PiecesInfo {
+    pub n_pieces: usize,    //Anzahl der Scheiben
+    pub n_different: usize, //Anzahl verschiedener Scheiben
+    pub n_multiple: usize,  //wie viele Käsescheiben mehrfach vorkommen
+    pub max_n: u32,         //die größte Anzahl derselben Käsescheibe
+}
+
 impl PiecesMap {
     //erzeugt eine PiecesMap aus einer Liste von Käsescheiben
     pub fn new(pieces: &Vec<Piece>) -> PiecesMap {
         //die base-HashMap,
         //benuzt FxHashMap, da diese schneller ist als die Standard-HashMap
         let mut pieces_map: FxHashMap<Piece, u32> = FxHashMap::default();
-        //die größte Anzahl derselben Käsescheibe
-        let mut max_n = 0;
-        //wie viele Käsescheiben mehrfach vorkommen
-        let mut n_multiple = 0;
         for piece in pieces {
-            //überprüft, ob die Käsescheibe schon in der HashMap ist
-            if let Some(n) = pieces_map.get_mut(piece) {
-                //wenn ja, wird die Anzahl der Scheibe in der HashMap um 1 erhöht
-                *n += 1;
-                n_multiple += 1;
-                if n > &mut max_n {
-                    max_n = *n;
-                }
-            } else {
-                //wenn nicht, wird die Käsescheibe neu in die HashMap eingefügt
-                pieces_map.insert(*piece, 1);
-            }
+            //erhöht die Anzahl der Scheibe in der HashMap um 1,
+            //falls sie noch nicht vorhanden ist, wird sie neu eingefügt
+            *pieces_map.entry(*piece).or_insert(0) += 1;
         }
-        //gebe Informationen über die Scheiben aus
-        println!("Informationen über die Käsescheiben:");
-        println!(
-            "\tMaximale Anzahl eines einzelnen Stücks: {}\n\tMehrfache Scheiben: {}\n\tAnzahl verschiener Scheiben: {}",
-            max_n,
-            n_multiple,
-            pieces_map.len()
-        );
-        println!();
         PiecesMap::new_from_map(pieces_map)
     }
+    //gibt Informationen über die noch vorhandenen Käsescheiben zurück
+    pub fn info(&self) -> PiecesInfo {
+        let mut info = PiecesInfo::default();
+        for (piece, n) in self.base.iter() {
+            let n = *self.added.get(piece).unwrap_or(n);
+            if n == 0 {
+                continue;
+            }
+            info.n_pieces += n as usize;
+            info.n_different += 1;
+            //alle Scheiben außer der ersten kommen mehrfach vor
+            info.n_multiple += n as usize - 1;
+            info.max_n = info.max_n.max(n);
+        }
+        info
+    }
 
     //erzeugt eine neue Instanz aus einer base-HashMap
     pub fn new_from_map(map: FxHashMap<Piece, u32>) -> Self {
@@ -71,6 +74,22 @@ impl PiecesMap {
             self.base.get(k)
         }
     }
+    //gibt die übrigen Käsescheiben mit ihrer Anzahl zurück, sortiert,
+    //sodass gleiche Käsescheiben immer dieselbe Liste ergeben
+    pub fn counts(&self) -> Vec<(Piece, u32)> {
+        let mut counts = self
+            .base
+            .keys()
+            .map(|k| (*k, *self.get(k).unwrap()))
+            .filter(|(_, n)| *n > 0)
+            .collect::<Vec<_>>();
+        counts.sort_unstable_by_key(|(piece, _)| (piece.0, piece.1));
+        counts
+    }
+    //gibt true zurück, wenn keine Käsescheiben mehr übrig sind
+    pub fn is_empty(&self) -> bool {
+        self.base.keys().all(|k| self.get(k) == Some(&0))
+    }
     //fügt eine Käsescheibe hinzu oder verändert die Anzahl der Käsescheibe
     //es wird nur die added-HashMap verändert, um Zeit beim Klonen zu sparen
     pub fn insert(&mut self, k: Piece, v: u32) -> Option<u32> {
@@ -144,3 +163,20 @@ impl PiecesMap {
         Self::new_from_map(new_map)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_depend_only_on_pieces_left() {
+        let pieces = PiecesMap::new(&vec![Piece(2, 1), Piece(3, 2), Piece(3, 2), Piece(4, 3)]);
+        // dieselben übrigen Scheiben, auf verschiedenen Wegen entfernt
+        let mut removed = pieces.clone_without(&vec![Piece(4, 3)]);
+        removed.insert(Piece(3, 2), 1);
+        let other = PiecesMap::new(&vec![Piece(3, 2), Piece(2, 1)]);
+        assert_eq!(removed.counts(), other.counts());
+        assert_eq!(other.counts(), vec![(Piece(2, 1), 1), (Piece(3, 2), 1)]);
+        assert_ne!(removed.counts(), pieces.counts());
+    }
+}
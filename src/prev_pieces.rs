@@ -14,6 +14,22 @@ pub struct PrevPieces {
     //die Anzahl der hypothetischen fehlenden Scheiben,
     //die zum Käse hinzugefügt wurden
     pub n_added: u32,
+    //wie viele hypothetische Scheiben direkt hintereinander am Ende der Liste stehen
+    pub run_added: u32,
+    //wie viele Folgen direkt aufeinanderfolgender hypothetischer Scheiben die Liste enthält
+    pub n_runs: u32,
+    //ein Fingerabdruck der verwendeten echten Scheiben, unabhängig von ihrer Reihenfolge,
+    //Pfade mit denselben Scheiben haben denselben Fingerabdruck, ob zwei Pfade mit gleichem
+    //Fingerabdruck wirklich dieselben Scheiben verwendet haben, prüft same_real_pieces
+    pub used_hash: u64,
+}
+//ein Fingerabdruck einer einzelnen Scheibe (splitmix64),
+//die Fingerabdrücke werden für PrevPieces::used_hash addiert
+fn piece_hash(piece: Piece) -> u64 {
+    let mut x = ((piece.0 as u64) << 32 | piece.1 as u64).wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
 }
 impl PrevPieces {
     //erzeugt eine neue Instanz
@@ -23,6 +39,9 @@ impl PrevPieces {
             start_piece: value,
             len: 1,
             n_added: 0,
+            run_added: 0,
+            n_runs: 0,
+            used_hash: 0,
         }
     }
     ///erzeugt eine neue Instanz, mit einer zusätzlichen (echten) Scheibe
@@ -32,6 +51,9 @@ impl PrevPieces {
             start_piece: self.start_piece,
             len: self.len + 1, //die Länge der Liste wird um 1 erhöht
             n_added: self.n_added,
+            run_added: 0, //die Folge hypothetischer Scheiben ist unterbrochen
+            n_runs: self.n_runs,
+            used_hash: self.used_hash.wrapping_add(piece_hash(value)),
         }
     }
     ///erzeugt eine neue Instanz, mit einer zusätzlichen (hypothetischen) Scheibe
@@ -41,7 +63,42 @@ impl PrevPieces {
             start_piece: self.start_piece,
             len: self.len,             //die Länge der Liste bleibt gleich
             n_added: self.n_added + 1, //die Anzahl der hypothetischen Scheiben wird um 1 erhöht
+            run_added: self.run_added + 1,
+            //eine neue Folge beginnt, wenn die Scheibe davor echt ist
+            n_runs: self.n_runs + (self.run_added == 0) as u32,
+            used_hash: self.used_hash,
+        }
+    }
+    ///gibt true zurück, wenn beide Listen dieselben echten Scheiben enthalten, unabhängig von ihrer
+    ///Reihenfolge, verglichen werden nur die Knoten nach dem letzten gemeinsamen Vorgänger
+    pub fn same_real_pieces(&self, other: &PrevPieces) -> bool {
+        if self.len != other.len {
+            return false;
+        }
+        //die Knoten der beiden Listen und wie viele Knoten sie noch haben,
+        //der letzte Knoten (die Startscheibe) ist keine verwendete Scheibe und wird nicht verglichen
+        let (mut node, mut other_node) = (self.curr.clone(), other.curr.clone());
+        let mut depth = self.len + self.n_added as usize;
+        let mut other_depth = other.len + other.n_added as usize;
+        let (mut pieces, mut other_pieces) = (vec![], vec![]);
+        while depth > other_depth {
+            node = node.step_back(&mut pieces);
+            depth -= 1;
+        }
+        while other_depth > depth {
+            other_node = other_node.step_back(&mut other_pieces);
+            other_depth -= 1;
+        }
+        //beide Listen sind jetzt gleich lang und werden gleichzeitig
+        //bis zum gemeinsamen Vorgänger oder zur Startscheibe zurückverfolgt
+        while depth > 1 && !Rc::ptr_eq(&node, &other_node) {
+            node = node.step_back(&mut pieces);
+            other_node = other_node.step_back(&mut other_pieces);
+            depth -= 1;
         }
+        pieces.sort_unstable_by_key(|piece| (piece.0, piece.1));
+        other_pieces.sort_unstable_by_key(|piece| (piece.0, piece.1));
+        pieces == other_pieces
     }
 }
 //ein Knoten der Liste
@@ -73,6 +130,13 @@ impl HistPoint {
             is_added,
         })
     }
+    //gibt den Vorgänger-Knoten zurück und merkt sich die Scheibe, wenn sie echt ist
+    fn step_back(self: &Rc<HistPoint>, pieces: &mut Vec<Piece>) -> Rc<HistPoint> {
+        if !self.is_added {
+            pieces.push(self.value);
+        }
+        self.prev.clone().unwrap()
+    }
     //gibt die Liste als Array zurück
     fn to_array(self: &Rc<HistPoint>) -> Vec<Rc<HistPoint>> {
         let mut nodes = vec![];
@@ -94,10 +158,13 @@ impl HistPoint {
             .map(|pt| pt.value)
             .collect()
     }
-    ///gibt die Liste als Array von Scheiben zurück,
-    /// egal ob sie echte oder hypothetische Scheiben sind
-    pub fn get_pieces(self: &Rc<HistPoint>) -> Vec<Piece> {
-        self.to_array().iter().map(|pt| pt.value).collect()
+    ///gibt die Liste als Array von Scheiben zurück, egal ob sie echte oder hypothetische Scheiben sind,
+    ///zusammen mit der Angabe, ob es sich um eine hypothetische Scheibe handelt
+    pub fn get_marked_pieces(self: &Rc<HistPoint>) -> Vec<(Piece, bool)> {
+        self.to_array()
+            .iter()
+            .map(|pt| (pt.value, pt.is_added))
+            .collect()
     }
 }
 impl Drop for PrevPieces {
@@ -0,0 +1,322 @@
+//Ein langsamer, aber vollständiger Löser für kleine Scheibenstapel
+//Anders als construct_cheeses werden hier keine Heuristiken verwendet,
+//stattdessen wird mit Backtracking jede mögliche Zerlegung ausprobiert,
+//deshalb eignet er sich zum Vergleich mit der eigentlichen Suche
+use std::{
+    collections::HashSet,
+    panic::{self, AssertUnwindSafe},
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::{
+    cheese::{Cheese, Piece},
+    cheese_builder::{construct_cheeses, SearchStats, SolverConfig},
+    pieces_map::PiecesMap,
+    solution::{Solution, SolutionPiece},
+    verify::verify_sequence,
+};
+
+//der Zustand der Suche
+struct Search {
+    kinds: FxHashMap<Piece, usize>, //Index jeder verschiedenen Scheibe
+    counts: Vec<usize>,             //wie viele Scheiben jeder Art noch übrig sind
+    n_left: usize,                  //wie viele Scheiben insgesamt noch übrig sind
+    max_cheeses: usize,
+    limit: Option<usize>,
+    finished: Vec<Vec<Piece>>, //die fertigen Käse als Scheibenreihenfolge
+    //der Käse, der gerade zusammengesetzt wird
+    current: Option<(Cheese, Vec<Piece>)>,
+    //die gefundenen Zerlegungen, die Käse sind sortiert, damit Zerlegungen,
+    //die sich nur in der Reihenfolge der Käse unterscheiden, nur einmal vorkommen
+    results: Vec<Vec<Vec<Piece>>>,
+    seen: HashSet<Vec<Vec<Piece>>>,
+}
+
+impl Search {
+    ///nimmt eine Scheibe vom Stapel, falls noch eine da ist
+    fn take(&mut self, piece: &Piece) -> bool {
+        match self.kinds.get(piece) {
+            Some(&i) if self.counts[i] > 0 => {
+                self.counts[i] -= 1;
+                self.n_left -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+    ///legt eine Scheibe zurück auf den Stapel
+    fn put_back(&mut self, piece: &Piece) {
+        self.counts[self.kinds[piece]] += 1;
+        self.n_left += 1;
+    }
+    ///gibt true zurück, wenn genug Zerlegungen gefunden wurden
+    fn is_done(&self) -> bool {
+        self.limit.is_some_and(|limit| self.results.len() >= limit)
+    }
+    ///probiert alle Möglichkeiten aus, den aktuellen Zustand fortzusetzen
+    fn search(&mut self) {
+        if self.is_done() {
+            return;
+        }
+        let Some((cheese, order)) = self.current.take() else {
+            if self.n_left == 0 {
+                let mut result = self.finished.clone();
+                result.sort_unstable_by_key(|order| {
+                    order.iter().map(|p| (p.0, p.1)).collect::<Vec<_>>()
+                });
+                if self.seen.insert(result.clone()) {
+                    self.results.push(result);
+                }
+                return;
+            }
+            if self.finished.len() >= self.max_cheeses {
+                return;
+            }
+            //jede verschiedene Scheibe wird als Startscheibe eines neuen Käses ausprobiert
+            let mut starts = self.kinds.keys().cloned().collect::<Vec<_>>();
+            starts.sort_unstable_by_key(|piece| (piece.0, piece.1));
+            for start in starts {
+                if self.take(&start) {
+                    self.current = Some((Cheese::new([start.0, start.1, 1]), vec![start]));
+                    self.search();
+                    self.current = None;
+                    self.put_back(&start);
+                }
+            }
+            return;
+        };
+        //der aktuelle Käse wird um eine Scheibe vergrößert,
+        //gleiche Seiten werden nur einmal ausprobiert
+        let mut sides = cheese.get_sides();
+        sides.dedup();
+        for side in sides {
+            if self.take(&side) {
+                let mut new_order = order.clone();
+                new_order.push(side);
+                self.current = Some((cheese.add_piece(side).unwrap(), new_order));
+                self.search();
+                self.current = None;
+                self.put_back(&side);
+            }
+        }
+        //oder der aktuelle Käse ist fertig,
+        //das lohnt sich nur, wenn danach noch ein Käse kommen darf oder alles verbraucht ist
+        if self.n_left == 0 || self.finished.len() + 1 < self.max_cheeses {
+            self.finished.push(order.clone());
+            self.search();
+            self.finished.pop();
+        }
+        self.current = Some((cheese, order));
+    }
+}
+
+///zählt alle Zerlegungen der Scheiben in höchstens max_cheeses Käse auf,
+///jede Zerlegung verwendet jede Scheibe genau einmal (ohne aufgegessene Scheiben)
+///Zerlegungen, die sich nur durch die Reihenfolge der Käse
+///oder durch das Vertauschen gleicher Scheiben unterscheiden, kommen nur einmal vor
+///falls limit angegeben ist, wird nach so vielen Zerlegungen abgebrochen
+///nur für kleine Stapel geeignet, da die Laufzeit exponentiell wächst
+pub fn reference_decompositions(
+    pieces: &[Piece],
+    max_cheeses: usize,
+    limit: Option<usize>,
+) -> Vec<Vec<Solution>> {
+    let mut kinds = FxHashMap::default();
+    let mut counts = vec![];
+    for piece in pieces {
+        let i = *kinds.entry(*piece).or_insert_with(|| {
+            counts.push(0);
+            counts.len() - 1
+        });
+        counts[i] += 1;
+    }
+    let mut search = Search {
+        kinds,
+        counts,
+        n_left: pieces.len(),
+        max_cheeses,
+        limit,
+        finished: vec![],
+        current: None,
+        results: vec![],
+        seen: HashSet::new(),
+    };
+    if !pieces.is_empty() {
+        search.search();
+    }
+    search
+        .results
+        .into_iter()
+        .map(|cheeses| {
+            cheeses
+                .into_iter()
+                .map(|order| {
+                    let pieces = order
+                        .into_iter()
+                        .map(|piece| SolutionPiece {
+                            piece,
+                            is_added: false,
+                        })
+                        .collect();
+                    Solution::from_pieces(pieces).expect("reference produced an invalid cheese")
+                })
+                .collect()
+        })
+        .collect()
+}
+
+///gibt die kleinste Anzahl an Käsen zurück, in die sich die Scheiben zerlegen lassen,
+///oder None, falls dafür mehr als max_cheeses Käse nötig sind
+pub fn min_cheeses(pieces: &[Piece], max_cheeses: usize) -> Option<usize> {
+    (1..=max_cheeses)
+        .find(|n_cheeses| !reference_decompositions(pieces, *n_cheeses, Some(1)).is_empty())
+}
+///das Ergebnis eines Vergleichs zwischen construct_cheeses und dem Referenz-Löser
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Differential {
+    ///beide finden eine Zerlegung, oder beide finden keine
+    Agree,
+    ///construct_cheeses ist abgestürzt
+    MainPanicked,
+    ///construct_cheeses hat nichts gefunden, obwohl es eine Zerlegung gibt
+    MainMissed,
+    ///construct_cheeses hat mehr Käse gefunden als nötig wären,
+    ///bzw. mehr als beim Vergleich erlaubt sind
+    MainSplit,
+    ///das Ergebnis von construct_cheeses ist keine gültige Zerlegung
+    MainInvalid,
+}
+
+///überprüft, ob die Käse genau die Scheiben des Stapels verwenden und gültig sind
+fn is_valid_decomposition(pieces: &[Piece], cheeses: &[Solution]) -> bool {
+    let mut used = vec![];
+    for cheese in cheeses {
+        let order = cheese.real_pieces();
+        if order.len() != cheese.pieces.len() || verify_sequence(&order, None).is_err() {
+            return false;
+        }
+        used.extend(order);
+    }
+    used.len() == pieces.len() && same_pieces(&used, pieces)
+}
+///überprüft, ob zwei Stapel dieselben Scheiben enthalten
+fn same_pieces(a: &[Piece], b: &[Piece]) -> bool {
+    let mut counts: FxHashMap<Piece, i64> = FxHashMap::default();
+    for piece in a {
+        *counts.entry(*piece).or_insert(0) += 1;
+    }
+    for piece in b {
+        *counts.entry(*piece).or_insert(0) -= 1;
+    }
+    counts.values().all(|n| *n == 0)
+}
+///vergleicht construct_cheeses (mit config, ohne fehlende Scheiben) mit dem Referenz-Löser,
+///der die kleinste Anzahl an Käsen mit höchstens max_cheeses Käsen bestimmt
+pub fn compare_with_reference(
+    pieces: &[Piece],
+    max_cheeses: usize,
+    config: &SolverConfig,
+) -> Differential {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let pieces_map = Box::new(PiecesMap::new(&pieces.to_vec()));
+        construct_cheeses(
+            pieces_map,
+            pieces.len(),
+            config,
+            &mut SearchStats::default(),
+        )
+    }));
+    let Ok(result) = result else {
+        return Differential::MainPanicked;
+    };
+    let found = result
+        .iter()
+        .map(|(cheese, path)| Solution::from_path(*cheese, path))
+        .collect::<Vec<_>>();
+    match (found.is_empty(), min_cheeses(pieces, max_cheeses)) {
+        (true, None) => Differential::Agree,
+        (true, Some(_)) => Differential::MainMissed,
+        (false, _) if !is_valid_decomposition(pieces, &found) => Differential::MainInvalid,
+        // Eine gültige Zerlegung mit höchstens max_cheeses Käsen hätte der Referenz-Löser gefunden
+        (false, None) => Differential::MainSplit,
+        (false, Some(min)) if found.len() > min => Differential::MainSplit,
+        (false, _) => Differential::Agree,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::generator::generate_pile;
+
+    // erzeugt einen zufällig aufgeschnittenen Stapel aus den Käsen sizes
+    fn pile(sizes: &[[u32; 3]], seed: u64) -> Vec<Piece> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        generate_pile(sizes, None, None, &mut rng).unwrap().pieces
+    }
+    fn exact() -> SolverConfig {
+        SolverConfig {
+            exact: true,
+            ..SolverConfig::default()
+        }
+    }
+
+    #[test]
+    fn min_cheeses_of_generated_cheese() {
+        for seed in 0..20 {
+            assert_eq!(min_cheeses(&pile(&[[4, 3, 2]], seed), 3), Some(1));
+        }
+    }
+    #[test]
+    fn min_cheeses_of_unrelated_pieces() {
+        let pieces = [Piece(1, 1), Piece(5, 3)];
+        assert_eq!(min_cheeses(&pieces, 1), None);
+        assert_eq!(min_cheeses(&pieces, 2), Some(2));
+    }
+    #[test]
+    fn more_cheeses_than_allowed_is_a_split() {
+        let pieces = [Piece(1, 1), Piece(5, 3)];
+        assert_eq!(
+            compare_with_reference(&pieces, 1, &exact()),
+            Differential::MainSplit
+        );
+        assert_eq!(
+            compare_with_reference(&pieces, 2, &exact()),
+            Differential::Agree
+        );
+    }
+    #[test]
+    fn exact_agrees_with_reference() {
+        for seed in 0..20 {
+            for size in [[3, 3, 2], [4, 3, 2], [5, 2, 2], [4, 4, 1]] {
+                let pieces = pile(&[size], seed);
+                assert_eq!(
+                    compare_with_reference(&pieces, 1, &exact()),
+                    Differential::Agree,
+                    "{:?}",
+                    pieces
+                );
+            }
+            let pieces = pile(&[[3, 2, 2], [4, 2, 1]], seed);
+            assert_eq!(
+                compare_with_reference(&pieces, 2, &exact()),
+                Differential::Agree,
+                "{:?}",
+                pieces
+            );
+        }
+    }
+    #[test]
+    fn greedy_results_are_valid() {
+        for seed in 0..20 {
+            for size in [[3, 3, 2], [4, 3, 2], [5, 4, 2]] {
+                let pieces = pile(&[size], seed);
+                let differential = compare_with_reference(&pieces, 1, &SolverConfig::default());
+                assert_ne!(differential, Differential::MainInvalid, "{:?}", pieces);
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::{fmt::Write as _, fs, time::Duration};
+
+use crate::{
+    cheese_builder::{Ambiguity, SearchStats},
+    pieces_map::PiecesInfo,
+    solution::{run_length_encode, Solution},
+    svg::render_svg,
+};
+
+//bis zu dieser Anzahl von Scheiben wird eine Darstellung des Käses eingebettet,
+//bei größeren Käsen würde die Datei zu groß werden
+const MAX_SVG_PIECES: usize = 5000;
+
+//die Stylesheets werden direkt eingebettet, damit die Datei ohne weitere Dateien funktioniert
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;max-width:60em}\
+table{border-collapse:collapse;margin-bottom:1em}\
+td,th{border:1px solid #ccc;padding:2px 8px;text-align:right}\
+th{background:#f3e3b0}\
+tr.added td{background:#cfe3ff;font-weight:bold}\
+svg{max-width:100%;height:auto}";
+
+///alles, was für den Bericht über einen Durchlauf benötigt wird
+pub struct Report {
+    //die Dateien, aus denen die Scheiben geladen wurden
+    pub files: Vec<String>,
+    //die Einstellungen als Name und Wert
+    pub config: Vec<(String, String)>,
+    //Informationen über die Eingabe
+    pub pieces_info: PiecesInfo,
+    //die gefundenen Käse
+    pub solutions: Vec<Solution>,
+    //wie lange die Suche gedauert hat
+    pub elapsed: Duration,
+    //Statistiken über die Suche
+    pub stats: SearchStats,
+}
+
+///ersetzt Zeichen, die in HTML eine besondere Bedeutung haben
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+///schreibt eine Tabelle mit zwei Spalten
+fn write_table(html: &mut String, rows: &[(&str, String)]) {
+    html.push_str("<table>\n");
+    for (name, value) in rows {
+        writeln!(
+            html,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            escape(name),
+            escape(value)
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n");
+}
+///schreibt einen gefundenen Käse mit seiner Scheibenliste,
+///aufgegessene (hypothetische) Scheiben werden hervorgehoben
+fn write_solution(html: &mut String, i: usize, solution: &Solution, ambiguity: Option<&Ambiguity>) {
+    writeln!(html, "<h2>Käse {}: {:?}</h2>", i + 1, solution.cheese.size).unwrap();
+    let mut rows = vec![
+        ("Scheiben", solution.pieces.len().to_string()),
+        ("davon aufgegessen", solution.n_added().to_string()),
+    ];
+    if let Some(ambiguity) = ambiguity {
+        rows.push((
+            "weitere Lösungen im selben Schritt",
+            format!(
+                "{}, erste weicht ab Scheibe {} ab",
+                ambiguity.n_solutions - 1,
+                ambiguity.diverges_at + 1
+            ),
+        ));
+    }
+    write_table(html, &rows);
+    if solution.pieces.len() <= MAX_SVG_PIECES {
+        html.push_str(&render_svg(solution));
+    }
+    //gleiche aufeinanderfolgende Scheiben werden zusammengefasst,
+    //damit die Liste auch bei großen Käsen übersichtlich bleibt
+    html.push_str(
+        "<table>\n<tr><th>Nr.</th><th>Anzahl</th><th>Scheibe</th><th>aufgegessen</th></tr>\n",
+    );
+    let mut n = 0;
+    for (count, piece) in run_length_encode(&solution.pieces) {
+        let class = if piece.is_added {
+            r#" class="added""#
+        } else {
+            ""
+        };
+        writeln!(
+            html,
+            "<tr{}><td>{}</td><td>{}</td><td>{} × {}</td><td>{}</td></tr>",
+            class,
+            n + 1,
+            count,
+            piece.piece.0,
+            piece.piece.1,
+            if piece.is_added { "ja" } else { "" }
+        )
+        .unwrap();
+        n += count;
+    }
+    html.push_str("</table>\n");
+}
+///erzeugt den Bericht als eigenständige HTML-Seite
+pub fn render_html(report: &Report) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"de\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Käse-Bericht</title>\n");
+    writeln!(html, "<style>{}</style>\n</head>\n<body>", STYLE).unwrap();
+    html.push_str("<h1>Käse-Bericht</h1>\n");
+
+    html.push_str("<h2>Eingabe</h2>\n");
+    let info = report.pieces_info;
+    write_table(
+        &mut html,
+        &[
+            ("Dateien", report.files.join(", ")),
+            ("Scheiben", info.n_pieces.to_string()),
+            ("verschiedene Scheiben", info.n_different.to_string()),
+            ("mehrfache Scheiben", info.n_multiple.to_string()),
+            ("maximale Anzahl eines Stücks", info.max_n.to_string()),
+        ],
+    );
+
+    html.push_str("<h2>Einstellungen</h2>\n");
+    let config = report
+        .config
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect::<Vec<_>>();
+    write_table(&mut html, &config);
+
+    html.push_str("<h2>Ergebnis</h2>\n");
+    write_table(
+        &mut html,
+        &[
+            ("gefundene Käse", report.solutions.len().to_string()),
+            ("Dauer der Suche", format!("{:?}", report.elapsed)),
+            ("erzeugte Pfade", report.stats.n_paths.to_string()),
+            (
+                "höchstens gleichzeitig verfolgte Pfade",
+                report.stats.peak_frontier.to_string(),
+            ),
+            (
+                "ausprobierte Startscheiben",
+                report.stats.starts.n_tried.to_string(),
+            ),
+            (
+                "verworfene Startscheiben (ohne passende zweite Scheibe / zu dick / ohne mögliche Größe)",
+                format!(
+                    "{} / {} / {}",
+                    report.stats.starts.n_unextendable,
+                    report.stats.starts.n_too_thick,
+                    report.stats.starts.n_infeasible
+                ),
+            ),
+            (
+                "zusammengeführte Pfade mit gleichem Zustand",
+                report.stats.n_transpositions.to_string(),
+            ),
+            (
+                "verworfene Pfade ohne mögliche Käsegröße",
+                report.stats.n_infeasible.to_string(),
+            ),
+            (
+                "wieder verworfene Käse (exakte Zerlegung)",
+                report.stats.n_backtracks.to_string(),
+            ),
+            (
+                "wegen zu vieler fehlender Scheiben verworfene Pfade",
+                report.stats.n_over_budget.to_string(),
+            ),
+        ],
+    );
+    for (i, solution) in report.solutions.iter().enumerate() {
+        let ambiguity = report
+            .stats
+            .ambiguities
+            .iter()
+            .find(|ambiguity| ambiguity.cheese_i == i);
+        write_solution(&mut html, i, solution, ambiguity);
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+///schreibt den Bericht in eine Datei
+pub fn write_html(path: &str, report: &Report) {
+    fs::write(path, render_html(report)).expect("couldn't write report");
+}
@@ -0,0 +1,85 @@
+use rustc_hash::FxHashMap;
+
+use crate::{cheese::Piece, solution::Solution};
+
+///vergleicht die von der Suche hinzugefügten Scheiben mit den wirklich gegessenen Scheiben
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    //die Größe des richtigen und des gefundenen Käses
+    pub truth_size: [u32; 3],
+    pub found_size: [u32; 3],
+    //wie viele Scheiben wirklich gegessen wurden
+    pub n_eaten: usize,
+    //wie viele Scheiben die Suche hinzugefügt hat
+    pub n_added: usize,
+    //wie viele der hinzugefügten Scheiben einer gegessenen Scheibe zugeordnet werden konnten
+    pub n_matched: usize,
+    //für jede zugeordnete Scheibe, wie weit ihre Position von der richtigen abweicht
+    pub position_errors: Vec<usize>,
+}
+
+impl Score {
+    ///stimmt die Größe des gefundenen Käses?
+    pub fn size_matches(&self) -> bool {
+        self.truth_size == self.found_size
+    }
+    ///welcher Anteil der hinzugefügten Scheiben wirklich gegessen wurde
+    pub fn precision(&self) -> f64 {
+        if self.n_added == 0 {
+            1.0
+        } else {
+            self.n_matched as f64 / self.n_added as f64
+        }
+    }
+    ///welcher Anteil der gegessenen Scheiben gefunden wurde
+    pub fn recall(&self) -> f64 {
+        if self.n_eaten == 0 {
+            1.0
+        } else {
+            self.n_matched as f64 / self.n_eaten as f64
+        }
+    }
+    ///die durchschnittliche Abweichung der Positionen
+    pub fn mean_position_error(&self) -> f64 {
+        if self.position_errors.is_empty() {
+            0.0
+        } else {
+            self.position_errors.iter().sum::<usize>() as f64 / self.position_errors.len() as f64
+        }
+    }
+}
+
+///gibt für jede Scheibe die Positionen zurück, an denen sie hypothetisch ist
+fn added_positions(solution: &Solution) -> FxHashMap<Piece, Vec<usize>> {
+    let mut positions: FxHashMap<Piece, Vec<usize>> = FxHashMap::default();
+    for (position, piece) in solution.pieces.iter().enumerate() {
+        if piece.is_added {
+            positions.entry(piece.piece).or_default().push(position);
+        }
+    }
+    positions
+}
+///vergleicht eine gefundene Lösung mit der richtigen Lösung (z.B. von generate),
+///in der die gegessenen Scheiben als hypothetisch markiert sind
+pub fn score(truth: &Solution, found: &Solution) -> Score {
+    let eaten = added_positions(truth);
+    let added = added_positions(found);
+    let mut position_errors = vec![];
+    for (piece, eaten_positions) in &eaten {
+        if let Some(added_positions) = added.get(piece) {
+            //gleiche Scheiben werden der Reihe nach zugeordnet,
+            //da beide Listen sortiert sind, ist die Summe der Abweichungen so am kleinsten
+            for (eaten, added) in eaten_positions.iter().zip(added_positions) {
+                position_errors.push(eaten.abs_diff(*added));
+            }
+        }
+    }
+    Score {
+        truth_size: truth.cheese.size,
+        found_size: found.cheese.size,
+        n_eaten: truth.n_added(),
+        n_added: found.n_added(),
+        n_matched: position_errors.len(),
+        position_errors,
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+};
+
+use crate::{
+    cheese::{Cheese, Piece},
+    prev_pieces::PrevPieces,
+};
+
+//trennt im kompakten Format die Anzahl von der Scheibe, z.B. "3 × 4 2"
+const RUN_SEPARATOR: char = '×';
+//markiert hypothetische (aufgegessene) Scheiben, z.B. "4 2 *"
+const ADDED_MARKER: char = '*';
+
+///eine Scheibe einer Lösung
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SolutionPiece {
+    pub piece: Piece,
+    pub is_added: bool, //wurde das Stück aufgegessen und ist hypothetisch?
+}
+
+///eine Scheibe zusammen mit ihrer Lage im fertigen Käse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlacedPiece {
+    pub origin: [u32; 3], //die Ecke der Scheibe mit den kleinsten Koordinaten
+    pub size: [u32; 3],   //die Ausdehnung in x-, y- und z-Richtung, eine davon ist 1
+    pub axis: usize,      //die Achse, entlang der der Käse durch die Scheibe gewachsen ist
+    pub is_added: bool,   //wurde das Stück aufgegessen und ist hypothetisch?
+}
+
+///ein fertiger Käse zusammen mit den Scheiben,
+///in der Reihenfolge in der sie an die Startscheibe angefügt wurden
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub cheese: Cheese,
+    pub pieces: Vec<SolutionPiece>,
+}
+
+impl Solution {
+    ///erzeugt eine Lösung aus einer Scheibenreihenfolge,
+    ///der Käse wird dabei Scheibe für Scheibe nachgebaut
+    ///gibt None zurück, falls die Scheiben keinen Käse ergeben
+    pub fn from_pieces(pieces: Vec<SolutionPiece>) -> Option<Self> {
+        let start = pieces.first()?.piece;
+        //wie in construct_cheeses beginnt der Käse mit der Dicke 0
+        let mut cheese = Cheese::new([start.0, start.1, 0]);
+        for piece in &pieces {
+            cheese = cheese.add_piece(piece.piece)?;
+        }
+        Some(Self { cheese, pieces })
+    }
+    ///erzeugt eine Lösung aus einem von construct_cheeses gefundenen Pfad
+    pub fn from_path(cheese: Cheese, path: &PrevPieces) -> Self {
+        let pieces = path
+            .curr
+            .get_marked_pieces()
+            .into_iter()
+            .rev()
+            .map(|(piece, is_added)| SolutionPiece { piece, is_added })
+            .collect();
+        Self { cheese, pieces }
+    }
+    ///liest eine Lösung aus einem Text, egal ob im normalen oder im kompakten Format
+    pub fn parse(s: &str) -> Result<Self, String> {
+        Self::from_pieces(parse_solution_pieces(s)?)
+            .ok_or_else(|| "pieces don't form a cheese".to_string())
+    }
+    ///liest eine Lösung aus einer Datei, egal ob im normalen oder im kompakten Format
+    pub fn load(path: &str) -> Result<Self, String> {
+        Self::parse(&read_file(path)?).map_err(|err| format!("{}: {}", path, err))
+    }
+    ///schreibt die Lösung in eine Datei,
+    ///bei compact werden gleiche aufeinanderfolgende Scheiben zusammengefasst
+    pub fn write(&self, path: &str, compact: bool) {
+        if compact {
+            write_pieces_compact(path, &self.pieces);
+        } else {
+            write_pieces(path, &self.pieces);
+        }
+    }
+    ///gibt die echten Scheiben zurück, d.h. die hypothetischen Scheiben werden ausgelassen
+    pub fn real_pieces(&self) -> Vec<Piece> {
+        self.pieces
+            .iter()
+            .filter(|piece| !piece.is_added)
+            .map(|piece| piece.piece)
+            .collect()
+    }
+    ///berechnet, wo die Scheiben im fertigen Käse liegen
+    ///anders als bei Cheese werden die Seitenlängen dabei nicht sortiert,
+    ///damit die Lage der Scheiben erhalten bleibt
+    ///gibt die Größe des fertigen Käses in x-, y- und z-Richtung und die Scheiben zurück
+    pub fn place_pieces(&self) -> ([u32; 3], Vec<PlacedPiece>) {
+        let start = self.pieces[0].piece;
+        //wie in construct_cheeses beginnt der Käse mit der Dicke 0
+        let mut size = [start.0, start.1, 0];
+        let placed = self
+            .pieces
+            .iter()
+            .map(|piece| {
+                //die Achse, entlang der der Käse durch die Scheibe wächst
+                let axis = Cheese::get_sides_n()
+                    .into_iter()
+                    .position(|(a, b)| Piece::new(size[a], size[b]) == piece.piece)
+                    .expect("piece doesn't fit to the cheese");
+                //die Scheibe wird immer auf der Seite mit den größeren Koordinaten angefügt
+                let mut origin = [0; 3];
+                origin[axis] = size[axis];
+                let mut piece_size = size;
+                piece_size[axis] = 1;
+                size[axis] += 1;
+                PlacedPiece {
+                    origin,
+                    size: piece_size,
+                    axis,
+                    is_added: piece.is_added,
+                }
+            })
+            .collect();
+        (size, placed)
+    }
+    ///gibt die Anzahl der hypothetischen Scheiben zurück
+    pub fn n_added(&self) -> usize {
+        self.pieces.iter().filter(|piece| piece.is_added).count()
+    }
+}
+
+///liest eine Zeile ein, entweder "w h" oder im kompakten Format "anzahl × w h",
+///hypothetische Scheiben haben am Ende zusätzlich ein "*"
+///gibt die Anzahl der Scheiben und die Scheibe zurück
+fn parse_line(line: &str) -> Result<(usize, SolutionPiece), String> {
+    //auch ein einfaches 'x' wird als Trennzeichen akzeptiert,
+    //da sich '×' schlecht tippen lässt
+    let (count, piece) = match line.split_once([RUN_SEPARATOR, 'x']) {
+        Some((count, piece)) => (
+            count
+                .trim()
+                .parse()
+                .map_err(|_| format!("couldn't parse number of pieces in run: {:?}", line))?,
+            piece,
+        ),
+        None => (1, line),
+    };
+    let (piece, is_added) = match piece.trim_end().strip_suffix(ADDED_MARKER) {
+        Some(piece) => (piece, true),
+        None => (piece, false),
+    };
+    let lens = piece.split_whitespace().collect::<Vec<&str>>();
+    if lens.len() != 2 {
+        return Err(format!("couldn't parse line: {:?}", line));
+    }
+    let piece = lens
+        .try_into()
+        .map_err(|_| format!("couldn't parse line: {:?}", line))?;
+    Ok((count, SolutionPiece { piece, is_added }))
+}
+///liest Scheiben aus einem Text, egal ob im normalen oder im kompakten Format
+pub fn parse_solution_pieces(s: &str) -> Result<Vec<SolutionPiece>, String> {
+    //funktioniert sowohl mit "\r\n" (Eingabedateien) als auch mit "\n" (Lösungsdateien)
+    let mut lines = s.lines();
+    let n_pieces: usize = lines
+        .next()
+        .ok_or_else(|| "empty data file".to_string())?
+        .trim()
+        .parse()
+        .map_err(|_| "couldn't extract number of pieces".to_string())?;
+    let mut pieces = Vec::with_capacity(n_pieces);
+    for line in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (count, piece) = parse_line(line)?;
+        pieces.extend(std::iter::repeat_n(piece, count));
+    }
+    if n_pieces != pieces.len() {
+        return Err(format!(
+            "expected {} pieces, found {}",
+            n_pieces,
+            pieces.len()
+        ));
+    }
+    Ok(pieces)
+}
+//liest eine Datei ein, der Fehler enthält den Pfad
+fn read_file(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("couldn't read {}: {}", path, err))
+}
+///liest Scheiben aus einer Datei, egal ob im normalen oder im kompakten Format
+///anders als bei Solution::load wird nicht überprüft, ob die Scheiben einen Käse ergeben
+pub fn read_solution_pieces(path: &str) -> Result<Vec<SolutionPiece>, String> {
+    parse_solution_pieces(&read_file(path)?).map_err(|err| format!("{}: {}", path, err))
+}
+///liest die echten Scheiben aus einem Text, egal ob im normalen oder im kompakten Format
+pub fn parse_pieces(s: &str) -> Result<Vec<Piece>, String> {
+    Ok(parse_solution_pieces(s)?
+        .into_iter()
+        .filter(|piece| !piece.is_added)
+        .map(|piece| piece.piece)
+        .collect())
+}
+///liest die echten Scheiben aus einer Datei, egal ob im normalen oder im kompakten Format
+pub fn read_pieces(path: &str) -> Result<Vec<Piece>, String> {
+    parse_pieces(&read_file(path)?).map_err(|err| format!("{}: {}", path, err))
+}
+///fasst aufeinanderfolgende gleiche Scheiben zusammen
+///gibt die Scheiben mit ihrer Anzahl zurück
+pub fn run_length_encode(pieces: &[SolutionPiece]) -> Vec<(usize, SolutionPiece)> {
+    let mut runs: Vec<(usize, SolutionPiece)> = vec![];
+    for piece in pieces {
+        match runs.last_mut() {
+            Some((count, last)) if last == piece => *count += 1,
+            _ => runs.push((1, *piece)),
+        }
+    }
+    runs
+}
+///schreibt eine Scheibe als "w h", bzw. "w h *" falls sie hypothetisch ist
+fn write_piece(writer: &mut impl Write, piece: &SolutionPiece) -> std::io::Result<()> {
+    write!(writer, "{} {}", piece.piece.0, piece.piece.1)?;
+    if piece.is_added {
+        write!(writer, " {}", ADDED_MARKER)?;
+    }
+    writeln!(writer)
+}
+/// Schreibt Käsescheiben in eine Datei
+pub fn write_pieces(path: &str, pieces: &[SolutionPiece]) {
+    let file = File::create(path).expect("couldn't create file");
+    let mut writer = BufWriter::new(file);
+    writeln!(writer, "{}", pieces.len()).expect("couldn't write number of pieces");
+    for piece in pieces {
+        write_piece(&mut writer, piece).expect("couldn't write piece");
+    }
+    writer.flush().expect("couldn't flush writer");
+}
+/// Schreibt einen Stapel von (echten) Käsescheiben im Format der Eingabedateien in eine Datei
+pub fn write_pile(path: &str, pieces: &[Piece]) {
+    let pieces = pieces
+        .iter()
+        .map(|piece| SolutionPiece {
+            piece: *piece,
+            is_added: false,
+        })
+        .collect::<Vec<_>>();
+    write_pieces(path, &pieces);
+}
+/// Schreibt Käsescheiben im kompakten Format in eine Datei,
+/// aufeinanderfolgende gleiche Scheiben werden als "anzahl × w h" geschrieben
+pub fn write_pieces_compact(path: &str, pieces: &[SolutionPiece]) {
+    let file = File::create(path).expect("couldn't create file");
+    let mut writer = BufWriter::new(file);
+    //die erste Zeile enthält wie im normalen Format die Gesamtzahl der Scheiben
+    writeln!(writer, "{}", pieces.len()).expect("couldn't write number of pieces");
+    for (count, piece) in run_length_encode(pieces) {
+        if count > 1 {
+            write!(writer, "{} {} ", count, RUN_SEPARATOR).expect("couldn't write piece");
+        }
+        write_piece(&mut writer, &piece).expect("couldn't write piece");
+    }
+    writer.flush().expect("couldn't flush writer");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn piece(a: u32, b: u32, is_added: bool) -> SolutionPiece {
+        SolutionPiece {
+            piece: Piece(a, b),
+            is_added,
+        }
+    }
+    // 4×2×0 → 4×2×2 → (4×2 fehlt) 4×3×2 → 4×4×3 → 4×4×4
+    fn example() -> Solution {
+        Solution::from_pieces(vec![
+            piece(4, 2, false),
+            piece(4, 2, false),
+            piece(4, 2, true),
+            piece(4, 3, false),
+            piece(4, 3, false),
+            piece(4, 4, false),
+        ])
+        .unwrap()
+    }
+    fn round_trip(compact: bool) -> Solution {
+        let path = std::env::temp_dir().join(format!(
+            "kaese_solution_{}_{}.txt",
+            std::process::id(),
+            compact
+        ));
+        let path = path.to_str().unwrap();
+        example().write(path, compact);
+        let loaded = Solution::load(path);
+        fs::remove_file(path).unwrap();
+        loaded.unwrap()
+    }
+
+    #[test]
+    fn round_trip_plain() {
+        assert_eq!(round_trip(false), example());
+    }
+    #[test]
+    fn round_trip_compact() {
+        assert_eq!(round_trip(true), example());
+    }
+    #[test]
+    fn parse_separators_and_marker() {
+        let with_times = "6\n2 × 4 2\n4 2 *\n2 × 4 3\n4 4\n";
+        let with_x = "6\r\n2 x 4 2\r\n4 2 *\r\n2x4 3\r\n4 4\r\n";
+        assert_eq!(Solution::parse(with_times), Ok(example()));
+        assert_eq!(Solution::parse(with_x), Ok(example()));
+        assert_eq!(
+            parse_solution_pieces("3\n2 × 4 2 *\n3 1\n"),
+            Ok(vec![
+                piece(4, 2, true),
+                piece(4, 2, true),
+                piece(3, 1, false)
+            ])
+        );
+    }
+    #[test]
+    fn parse_malformed() {
+        for s in [
+            "",
+            "zwei\n4 2\n",
+            "2\n4 2\n",
+            "1\n4 2 1\n",
+            "1\n4\n",
+            "1\nx 4 2\n",
+            "1\n4 a\n",
+        ] {
+            assert!(parse_solution_pieces(s).is_err(), "{:?}", s);
+        }
+        // die Scheiben passen nicht aneinander
+        assert!(Solution::parse("2\n4 2\n3 3\n").is_err());
+        assert!(Solution::load("does/not/exist.txt").is_err());
+    }
+}
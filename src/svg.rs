@@ -0,0 +1,122 @@
+use std::{fmt::Write as _, fs};
+
+use crate::solution::{PlacedPiece, Solution};
+
+//Breite des Bildes in Pixeln, die Höhe ergibt sich aus dem Käse
+const IMAGE_WIDTH: f64 = 800.0;
+//Abstand zum Rand des Bildes in Pixeln
+const MARGIN: f64 = 10.0;
+
+///projiziert einen Punkt isometrisch auf die Bildebene
+fn project(point: [f64; 3]) -> (f64, f64) {
+    let (sin, cos) = 30f64.to_radians().sin_cos();
+    (
+        (point[0] - point[1]) * cos,
+        (point[0] + point[1]) * sin - point[2],
+    )
+}
+///gibt die Farbe einer Scheibe zurück, echte Scheiben in Käsefarben,
+///hypothetische Scheiben werden blau hervorgehoben
+///brightness unterscheidet die drei sichtbaren Seiten einer Scheibe
+fn piece_color(i: usize, is_added: bool, brightness: f64) -> String {
+    if is_added {
+        format!("hsl(210, 90%, {:.0}%)", 35.0 + 20.0 * brightness)
+    } else {
+        //aufeinanderfolgende Scheiben bekommen leicht unterschiedliche Farbtöne
+        let hue = 35 + (i * 7) % 25;
+        format!("hsl({}, 85%, {:.0}%)", hue, 45.0 + 25.0 * brightness)
+    }
+}
+///gibt die drei sichtbaren Seiten einer Scheibe (oben, rechts, links)
+///als Eckpunkte zusammen mit ihrer Helligkeit zurück
+fn visible_faces(piece: &PlacedPiece) -> [([[f64; 3]; 4], f64); 3] {
+    let [x0, y0, z0] = piece.origin.map(f64::from);
+    let [x1, y1, z1] = [0, 1, 2].map(|i| f64::from(piece.origin[i] + piece.size[i]));
+    [
+        (
+            [[x0, y0, z1], [x1, y0, z1], [x1, y1, z1], [x0, y1, z1]],
+            1.0,
+        ),
+        (
+            [[x1, y0, z0], [x1, y1, z0], [x1, y1, z1], [x1, y0, z1]],
+            0.6,
+        ),
+        (
+            [[x0, y1, z0], [x1, y1, z0], [x1, y1, z1], [x0, y1, z1]],
+            0.3,
+        ),
+    ]
+}
+///zeichnet den fertigen Käse isometrisch als SVG,
+///jede Scheibe wird als eigene farbige Schicht gezeichnet
+pub fn render_svg(solution: &Solution) -> String {
+    let (size, pieces) = solution.place_pieces();
+    //die Ecken des Käses bestimmen, wie groß das Bild wird
+    let size = size.map(f64::from);
+    let corners = [
+        [0.0, 0.0, size[2]],
+        [size[0], 0.0, 0.0],
+        [0.0, size[1], 0.0],
+        [size[0], size[1], 0.0],
+    ]
+    .map(project);
+    let min_x = corners[2].0;
+    let max_x = corners[1].0;
+    let min_y = corners[0].1;
+    let max_y = corners[3].1;
+    let scale = (IMAGE_WIDTH - 2.0 * MARGIN) / (max_x - min_x).max(1.0);
+    let height = (max_y - min_y) * scale + 2.0 * MARGIN;
+    let to_image = |point: [f64; 3]| {
+        let (x, y) = project(point);
+        ((x - min_x) * scale + MARGIN, (y - min_y) * scale + MARGIN)
+    };
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {:.0} {:.0}">"#,
+        IMAGE_WIDTH, height, IMAGE_WIDTH, height
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        "<title>Käse {:?}, {} Scheiben, davon {} hypothetisch</title>",
+        solution.cheese.size,
+        solution.pieces.len(),
+        solution.n_added()
+    )
+    .unwrap();
+    //die Scheiben werden in der Reihenfolge gezeichnet, in der sie angefügt wurden,
+    //da spätere Scheiben immer weiter außen liegen, verdecken sie die früheren richtig
+    for (i, piece) in pieces.iter().enumerate() {
+        let stroke = if piece.is_added { 1.0 } else { 0.3 };
+        write!(
+            svg,
+            r#"<g id="piece-{}" stroke="black" stroke-width="{}">"#,
+            i, stroke
+        )
+        .unwrap();
+        for (corners, brightness) in visible_faces(piece) {
+            let points = corners
+                .map(|corner| {
+                    let (x, y) = to_image(corner);
+                    format!("{:.2},{:.2}", x, y)
+                })
+                .join(" ");
+            write!(
+                svg,
+                r#"<polygon points="{}" fill="{}"/>"#,
+                points,
+                piece_color(i, piece.is_added, brightness)
+            )
+            .unwrap();
+        }
+        writeln!(svg, "</g>").unwrap();
+    }
+    writeln!(svg, "</svg>").unwrap();
+    svg
+}
+///schreibt die isometrische Darstellung des Käses in eine Datei
+pub fn write_svg(path: &str, solution: &Solution) {
+    fs::write(path, render_svg(solution)).expect("couldn't write svg file");
+}
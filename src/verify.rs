@@ -0,0 +1,186 @@
+//Eine unabhängige Überprüfung von Lösungen
+//Hier wird bewusst nichts aus der Suche (Cheese::find_new_sides, PiecesMap) verwendet,
+//damit Fehler in der Suche nicht auch die Überprüfung betreffen
+use std::{collections::BTreeMap, fmt};
+
+use crate::cheese::{Cheese, Piece};
+
+///Fehler, die bei der Überprüfung einer Scheibenreihenfolge gefunden werden
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    ///die Reihenfolge enthält keine Scheiben
+    Empty,
+    ///die Scheibe in Schritt step (ab 0 gezählt) passt an keine Seite des Käses
+    NoMatchingSide {
+        step: usize,
+        piece: Piece,
+        size: [u32; 3], //die Größe des Käses vor dem Schritt
+    },
+    ///eine Scheibe aus dem Stapel kommt in der Reihenfolge seltener vor als im Stapel
+    MissingPiece { piece: Piece, missing: usize },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::Empty => write!(f, "die Lösung enthält keine Scheiben"),
+            VerifyError::NoMatchingSide { step, piece, size } => write!(
+                f,
+                "Scheibe {} ({}x{}) passt an keine Seite des Käses {:?}",
+                step + 1,
+                piece.0,
+                piece.1,
+                size
+            ),
+            VerifyError::MissingPiece { piece, missing } => write!(
+                f,
+                "Scheibe {}x{} fehlt {} mal in der Lösung",
+                piece.0, piece.1, missing
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+///sortiert die Seitenlängen absteigend, wie bei Cheese
+fn sorted(mut size: [u32; 3]) -> [u32; 3] {
+    size.sort_unstable_by(|a, b| b.cmp(a));
+    size
+}
+///die Seitenlängen einer Scheibe, die längere zuerst
+fn piece_key(piece: &Piece) -> (u32, u32) {
+    (piece.0.max(piece.1), piece.0.min(piece.1))
+}
+///überprüft, ob die Scheiben in dieser Reihenfolge einen Käse ergeben
+///die erste Scheibe ist die Startscheibe (Dicke 1), jede weitere Scheibe muss genau
+///auf eine Seite des bisherigen Käses passen
+///wenn pile angegeben ist, muss jede Scheibe aus pile in der Reihenfolge vorkommen,
+///zusätzliche Scheiben in der Reihenfolge gelten als hypothetisch (aufgegessen)
+///gibt den fertigen Käse zurück
+pub fn verify_sequence(pieces: &[Piece], pile: Option<&[Piece]>) -> Result<Cheese, VerifyError> {
+    let start = piece_key(pieces.first().ok_or(VerifyError::Empty)?);
+    //die Seitenlängen werden nicht sortiert, damit die Überprüfung möglichst einfach bleibt
+    let mut size = [start.0, start.1, 1];
+    for (step, piece) in pieces.iter().enumerate().skip(1) {
+        let key = piece_key(piece);
+        //die Achse, entlang der der Käse wächst: die beiden anderen Seitenlängen
+        //müssen genau zur Scheibe passen
+        let axis = (0..3).find(|&axis| {
+            let a = size[(axis + 1) % 3];
+            let b = size[(axis + 2) % 3];
+            (a.max(b), a.min(b)) == key
+        });
+        match axis {
+            Some(axis) => size[axis] += 1,
+            None => {
+                return Err(VerifyError::NoMatchingSide {
+                    step,
+                    piece: *piece,
+                    size: sorted(size),
+                })
+            }
+        }
+    }
+    if let Some(pile) = pile {
+        //zählt, wie oft jede Scheibe in der Reihenfolge fehlt
+        let mut counts: BTreeMap<(u32, u32), i64> = BTreeMap::new();
+        for piece in pile {
+            *counts.entry(piece_key(piece)).or_insert(0) += 1;
+        }
+        for piece in pieces {
+            *counts.entry(piece_key(piece)).or_insert(0) -= 1;
+        }
+        if let Some(((a, b), missing)) = counts.into_iter().find(|(_, n)| *n > 0) {
+            return Err(VerifyError::MissingPiece {
+                piece: Piece(a, b),
+                missing: missing as usize,
+            });
+        }
+    }
+    Ok(Cheese::new(sorted(size)))
+}
+///vergleicht die echten Scheiben einer Lösung mit dem Stapel, gleiche Scheiben werden gezählt
+///gibt die Scheiben zurück, die nicht gleich oft vorkommen, mit der Anzahl, wie oft sie zu oft
+///(positiv) bzw. zu selten (negativ) in der Lösung sind, leer, wenn beide übereinstimmen
+pub fn compare_pieces(real: &[Piece], pile: &[Piece]) -> Vec<(Piece, i64)> {
+    let mut counts: BTreeMap<(u32, u32), i64> = BTreeMap::new();
+    for piece in real {
+        *counts.entry(piece_key(piece)).or_insert(0) += 1;
+    }
+    for piece in pile {
+        *counts.entry(piece_key(piece)).or_insert(0) -= 1;
+    }
+    counts
+        .into_iter()
+        .filter(|(_, n)| *n != 0)
+        .map(|((a, b), n)| (Piece(a, b), n))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3×2×1 → 3×2×2 → 3×3×2 → 3×3×3
+    const ORDER: [Piece; 4] = [Piece(3, 2), Piece(3, 2), Piece(3, 2), Piece(3, 3)];
+
+    #[test]
+    fn accepts_valid_order() {
+        assert_eq!(verify_sequence(&ORDER, None), Ok(Cheese::new([3, 3, 3])));
+        // die Reihenfolge der Scheiben im Stapel ist egal
+        let mut pile = ORDER.to_vec();
+        pile.reverse();
+        assert_eq!(
+            verify_sequence(&ORDER, Some(&pile)),
+            Ok(Cheese::new([3, 3, 3]))
+        );
+        // Scheiben, die nicht im Stapel sind, gelten als hypothetisch
+        assert_eq!(
+            verify_sequence(&ORDER, Some(&ORDER[..3])),
+            Ok(Cheese::new([3, 3, 3]))
+        );
+        // die Seiten einer Scheibe dürfen vertauscht sein
+        let turned = [Piece(2, 3), Piece(3, 2), Piece(2, 3), Piece(3, 3)];
+        assert_eq!(verify_sequence(&turned, None), Ok(Cheese::new([3, 3, 3])));
+    }
+    #[test]
+    fn rejects_wrong_piece() {
+        let order = [Piece(3, 2), Piece(3, 2), Piece(3, 3)];
+        assert_eq!(
+            verify_sequence(&order, None),
+            Err(VerifyError::NoMatchingSide {
+                step: 2,
+                piece: Piece(3, 3),
+                size: [3, 2, 2],
+            })
+        );
+        assert_eq!(verify_sequence(&[], None), Err(VerifyError::Empty));
+    }
+    #[test]
+    fn rejects_piece_missing_from_order() {
+        let mut pile = ORDER.to_vec();
+        pile.push(Piece(3, 3));
+        pile.push(Piece(3, 3));
+        assert_eq!(
+            verify_sequence(&ORDER, Some(&pile)),
+            Err(VerifyError::MissingPiece {
+                piece: Piece(3, 3),
+                missing: 2,
+            })
+        );
+    }
+    #[test]
+    fn real_pieces_must_equal_pile() {
+        let pile = &ORDER[..3];
+        assert!(compare_pieces(pile, pile).is_empty());
+        // eine Scheibe aus dem Stapel ist als hypothetisch markiert, dafür ist eine Scheibe
+        // echt, die nicht im Stapel ist, die Reihenfolge ergibt trotzdem einen Käse
+        assert!(verify_sequence(&ORDER, None).is_ok());
+        let real = [Piece(3, 2), Piece(3, 2), Piece(3, 3)];
+        assert_eq!(
+            compare_pieces(&real, pile),
+            vec![(Piece(3, 2), -1), (Piece(3, 3), 1)]
+        );
+    }
+}
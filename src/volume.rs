@@ -0,0 +1,176 @@
+//Invarianten über Fläche und Volumen, mit denen sich aussichtslose Pfade früh erkennen lassen
+//Jede Scheibe trägt genau ihre Fläche zum Volumen des fertigen Käses bei und vergrößert
+//eine Seitenlänge um 1, besteht ein Käse aus allen Scheiben, liegen damit sowohl das Volumen
+//als auch die Summe der Seitenlängen des fertigen Käses fest
+//Außerdem lassen sich mit einer bekannten Größe oder einem bekannten Volumen
+//die möglichen Größen der Käse einschränken
+use std::ops::RangeInclusive;
+
+use rustc_hash::FxHashMap;
+
+use crate::{cheese::Piece, pieces_map::PiecesMap};
+
+///die möglichen Größen der fertigen Käse für jede Startscheibe
+#[derive(Debug, Clone, Default)]
+pub struct FeasibleSizes {
+    sizes: FxHashMap<Piece, Vec<[u32; 3]>>,
+}
+
+impl FeasibleSizes {
+    ///berechnet die möglichen Größen eines Käses, der aus allen Scheiben besteht,
+    ///die noch vorhanden sind (siehe candidate_sizes)
+    pub fn new(pieces: &PiecesMap) -> Self {
+        Self::from_candidates(pieces, &candidate_sizes(pieces, None, false), true)
+    }
+    ///verteilt die Größen candidates (längste Seite zuerst) auf die Startscheiben, die in sie passen
+    ///mit all_pieces muss der Käse alle Scheiben verwenden, dann passt zu jeder Startscheibe
+    ///nur eine Summe der Seitenlängen
+    pub fn from_candidates(pieces: &PiecesMap, candidates: &[[u32; 3]], all_pieces: bool) -> Self {
+        let (n_pieces, _) = pile_size(pieces);
+        let sizes = pieces
+            .base
+            .keys()
+            .filter(|piece| pieces.get(piece).is_some_and(|n| *n > 0))
+            .map(|start| {
+                // Der Käse beginnt mit der Größe [start.0, start.1, 0]
+                // und jede Scheibe vergrößert eine Seitenlänge um 1
+                let sum = n_pieces + start.0 as u64 + start.1 as u64;
+                let sizes = candidates
+                    .iter()
+                    .filter(|size| size[0] >= start.0 && size[1] >= start.1)
+                    .filter(|size| {
+                        !all_pieces || size.iter().map(|len| *len as u64).sum::<u64>() == sum
+                    })
+                    .cloned()
+                    .collect();
+                (*start, sizes)
+            })
+            .collect();
+        Self { sizes }
+    }
+    ///gibt die möglichen Größen für eine Startscheibe zurück, die längste Seite zuerst
+    pub fn sizes(&self, start: &Piece) -> &[[u32; 3]] {
+        self.sizes.get(start).map_or(&[], |sizes| sizes.as_slice())
+    }
+    ///überprüft, ob ein Käse mit dieser Startscheibe noch eine der möglichen Größen erreichen kann,
+    ///da die Seitenlängen nur wachsen, darf keine größer als die der möglichen Größe sein
+    pub fn can_reach(&self, start: &Piece, size: [u32; 3]) -> bool {
+        self.sizes(start)
+            .iter()
+            .any(|feasible| size.iter().zip(feasible).all(|(len, max)| len <= max))
+    }
+}
+///gibt die Anzahl der Scheiben und ihre gesamte Fläche zurück
+pub fn pile_size(pieces: &PiecesMap) -> (u64, u64) {
+    pieces
+        .base
+        .keys()
+        .map(|piece| (*pieces.get(piece).unwrap() as u64, piece))
+        .fold((0, 0), |(n_pieces, area), (n, piece)| {
+            (n_pieces + n, area + n * piece.0 as u64 * piece.1 as u64)
+        })
+}
+//gibt alle Teiler einer Zahl aufsteigend zurück
+fn divisors(n: u64) -> Vec<u64> {
+    let mut small = vec![];
+    let mut large = vec![];
+    let mut d = 1;
+    while d * d <= n {
+        if n.is_multiple_of(d) {
+            small.push(d);
+            if d * d != n {
+                large.push(n / d);
+            }
+        }
+        d += 1;
+    }
+    small.extend(large.into_iter().rev());
+    small
+}
+///listet die möglichen Größen (längste Seite zuerst) von Käsen mit dem Volumen volume auf,
+///ohne volume besteht der Käse aus allen Scheiben und hat ihre gesamte Fläche als Volumen
+///Ist volume mindestens so groß wie die gesamte Fläche, stammen alle Scheiben von einem Käse,
+///dann müssen auch die größten Scheiben in ihn passen
+///Fehlen keine Scheiben, sind zusätzlich die Summe der Seitenlängen (siehe FeasibleSizes)
+///und eine Seite des Käses bekannt, da die zuletzt abgeschnittene Scheibe eine ganze Seite ist
+pub fn candidate_sizes(
+    pieces: &PiecesMap,
+    volume: Option<u64>,
+    find_missing: bool,
+) -> Vec<[u32; 3]> {
+    let (n_pieces, area) = pile_size(pieces);
+    let volume = volume.unwrap_or(area);
+    let kinds = pieces
+        .base
+        .keys()
+        .filter(|piece| pieces.get(piece).is_some_and(|n| *n > 0))
+        .cloned()
+        .collect::<Vec<_>>();
+    if volume == 0 || kinds.is_empty() {
+        return vec![];
+    }
+    let single = volume >= area;
+    let exact = volume == area && !find_missing;
+    let sums = if exact {
+        let sum = |piece: &Piece| n_pieces + piece.0 as u64 + piece.1 as u64;
+        kinds.iter().map(sum).min().unwrap()..=kinds.iter().map(sum).max().unwrap()
+    } else {
+        0..=u64::MAX
+    };
+    let max_0 = kinds.iter().map(|piece| piece.0).max().unwrap();
+    let max_1 = kinds.iter().map(|piece| piece.1).max().unwrap();
+    final_sizes(volume, sums)
+        .into_iter()
+        .filter(|size| !single || (size[0] >= max_0 && size[1] >= max_1))
+        .filter(|size| {
+            let faces = [
+                Piece(size[0], size[1]),
+                Piece(size[0], size[2]),
+                Piece(size[1], size[2]),
+            ];
+            !exact || faces.iter().any(|face| kinds.contains(face))
+        })
+        .collect()
+}
+//gibt alle Größen a>=b>=c mit dem Volumen volume zurück, deren Summe der Seitenlängen in sums liegt
+fn final_sizes(volume: u64, sums: RangeInclusive<u64>) -> Vec<[u32; 3]> {
+    let divisors = divisors(volume);
+    let mut sizes = vec![];
+    for &c in &divisors {
+        if c.saturating_mul(c).saturating_mul(c) > volume {
+            break;
+        }
+        let rest = volume / c;
+        for &b in divisors.iter().skip_while(|b| **b < c) {
+            if b.saturating_mul(b) > rest {
+                break;
+            }
+            let a = rest / b;
+            if !rest.is_multiple_of(b) || !sums.contains(&(a + b + c)) {
+                continue;
+            }
+            //Käse mit Seiten, die nicht in u32 passen, gibt es nicht
+            if let (Ok(a), Ok(b), Ok(c)) = (u32::try_from(a), u32::try_from(b), u32::try_from(c)) {
+                sizes.push([a, b, c]);
+            }
+        }
+    }
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn final_sizes_fit_in_u32() {
+        let volume = 2 * (u32::MAX as u64 + 1);
+        let sizes = final_sizes(volume, 0..=u64::MAX);
+        assert!(!sizes.is_empty());
+        for size in &sizes {
+            assert_eq!(size.iter().map(|len| *len as u64).product::<u64>(), volume);
+        }
+        // 2^32 × 2 × 1 passt nicht in u32
+        assert!(!sizes.contains(&[0, 2, 1]));
+    }
+}